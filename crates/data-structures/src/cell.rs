@@ -1,11 +1,12 @@
 #![allow(unused)]
 use std::borrow::Cow;
+use std::cell::UnsafeCell;
+use std::cmp::Ordering;
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::iter::{Extend, IntoIterator, Iterator};
 use std::ops::Deref;
-
-use unique_pointer::{RefCounter, UniquePointer};
+use std::rc::{Rc, Weak};
 
 use crate::{AsSymbol, AsValue, Quotable, Symbol, Value};
 pub trait ListIterator<'c, T: AsCell<'c>>: IntoIterator<Item = T> + Debug + Quotable {
@@ -17,39 +18,123 @@ pub trait AsCell<'c>: Quotable {
     fn as_cell(&self) -> Cell<'c>;
 }
 
-#[derive(Eq, PartialOrd, Ord)]
+/// a cons cell.
+///
+/// Clones of a cell share its node, which is freed when the last
+/// of them is dropped, so that changes made with
+/// [`set_car`](Cell::set_car) and [`set_cdr`](Cell::set_cdr) are seen
+/// by every reference to the cell.
 pub struct Cell<'c> {
-    pub head: UniquePointer<Value<'c>>,
-    pub tail: UniquePointer<Cell<'c>>,
-    pub refs: RefCounter,
+    node: Option<Rc<UnsafeCell<Node<'c>>>>,
     pub quoted: bool,
 }
 
+/// a reference to a cell that does not keep it alive, made by
+/// [`Cell::downgrade`]. The [`identity`](Cell::identity) of the cell
+/// is not reused while it exists.
+#[derive(Clone, Default)]
+pub struct WeakCell<'c> {
+    node: Weak<UnsafeCell<Node<'c>>>,
+}
+
+impl<'c> WeakCell<'c> {
+    /// true once the cell has been freed
+    pub fn is_dropped(&self) -> bool {
+        self.node.strong_count() == 0
+    }
+}
+
+impl Debug for WeakCell<'_> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "WeakCell({:p})", self.node.as_ptr())
+    }
+}
+
+#[derive(Default)]
+struct Node<'c> {
+    head: Option<Value<'c>>,
+    tail: Option<Cell<'c>>,
+    /// the cdr of the last cell of an improper list when it is not a
+    /// list, such as `b` in `(a . b)`
+    dotted: Option<Value<'c>>,
+}
+
 impl<'c> Cell<'c> {
     pub fn nil() -> Cell<'c> {
         Cell::quoted(Option::<Value<'c>>::None, false)
     }
 
     pub fn quoted<T: AsValue<'c>>(item: Option<T>, quoted: bool) -> Cell<'c> {
-        let mut cell = Cell {
-            head: UniquePointer::<Value<'c>>::null(),
-            tail: UniquePointer::<Cell<'c>>::null(),
-            refs: RefCounter::new(),
-            quoted,
-        };
-        cell.incr_ref();
+        let mut cell = Cell { node: None, quoted };
         if let Some(item) = item {
             cell.write(item.as_value());
         }
         cell
     }
 
+    fn node(&self) -> Option<&Node<'c>> {
+        self.node.as_ref().map(|node| unsafe { &*node.get() })
+    }
+
+    fn node_mut(&mut self) -> &mut Node<'c> {
+        let node = self.node.get_or_insert_with(Default::default);
+        unsafe { &mut *node.get() }
+    }
+
+    fn head_ref(&self) -> Option<&Value<'c>> {
+        self.node().and_then(|node| node.head.as_ref())
+    }
+
+    fn tail_ref(&self) -> Option<&Cell<'c>> {
+        self.node().and_then(|node| node.tail.as_ref())
+    }
+
+    fn dotted_ref(&self) -> Option<&Value<'c>> {
+        self.node().and_then(|node| node.dotted.as_ref())
+    }
+
     pub fn is_nil(&self) -> bool {
-        self.head.is_null() && self.tail.is_null()
+        self.head_ref().is_none() && self.tail_ref().is_none() && self.dotted_ref().is_none()
+    }
+
+    /// true when this cell has no cdr, neither a tail nor a dotted
+    /// value
+    pub(crate) fn is_last(&self) -> bool {
+        self.tail_ref().is_none() && self.dotted_ref().is_none()
+    }
+
+    /// true when `self` and `other` are the same cell rather than
+    /// equal ones.
+    ///
+    /// Clones of a cell share its node, so a cell keeps its identity
+    /// when bound, returned by `cdr` or made the tail of another cell
+    /// with `cons` or `setcdr`. Empty cells are all the same.
+    pub fn is_same(&self, other: &Cell<'c>) -> bool {
+        (self.is_nil() && other.is_nil())
+            || match (&self.node, &other.node) {
+                (Some(node), Some(other)) => Rc::ptr_eq(node, other),
+                _ => false,
+            }
+    }
+
+    /// an address that identifies the cell the way
+    /// [`is_same`](Cell::is_same) does, for hashing by identity
+    pub fn identity(&self) -> usize {
+        match &self.node {
+            Some(node) if !self.is_nil() => Rc::as_ptr(node) as usize,
+            _ => 0,
+        }
+    }
+
+    /// a reference to this cell that does not keep it alive
+    pub fn downgrade(&self) -> WeakCell<'c> {
+        WeakCell {
+            node: self.node.as_ref().map(Rc::downgrade).unwrap_or_default(),
+        }
     }
 
     pub fn unwrap_value(&self) -> Value<'c> {
-        if self.tail.is_null() {
+        if self.is_last() {
             match self.head() {
                 Some(head) => head.unwrap_list(),
                 None => Value::Nil,
@@ -68,7 +153,7 @@ impl<'c> Cell<'c> {
     }
 
     pub fn head(&self) -> Option<Value<'c>> {
-        self.head.try_read()
+        self.head_ref().cloned()
     }
 
     pub fn push_value(&mut self, value: Value<'c>) {
@@ -82,39 +167,62 @@ impl<'c> Cell<'c> {
         if new.is_nil() {
             return;
         }
-        let mut new = new.clone();
-        self.incr_ref();
-
-        if self.head.is_null() {
-            if !new.head.is_null() {
-                self.swap_head(&mut new);
-            }
 
-            if new.tail.is_not_null() {
-                let tail = new.tail.inner_mut();
-                if new.head.is_not_null() {
-                    tail.head.write_ref(new.head.inner_ref());
-
-                }
+        if self.head_ref().is_none() {
+            let head = new.head();
+            let rest = Cell {
+                node: Some(Rc::new(UnsafeCell::new(Node {
+                    head: None,
+                    tail: new.tail_ref().cloned(),
+                    dotted: new.dotted_ref().cloned(),
+                }))),
+                quoted: false,
+            };
+            let node = self.node_mut();
+            if head.is_some() {
+                node.head = head;
             }
-            self.tail = UniquePointer::from(new);
+            node.tail = Some(rest);
         } else {
-            if self.tail.is_null() {
-                self.tail = UniquePointer::from(new);
-            } else {
-                self.tail.inner_mut().add(&new);
+            let node = self.node_mut();
+            match node.tail.as_mut() {
+                None => node.tail = Some(new.clone()),
+                Some(tail) => {
+                    // a tail shared with another list is copied
+                    // rather than extended under it
+                    if tail.node.as_ref().is_some_and(|node| Rc::strong_count(node) > 1) {
+                        *tail = tail.copy();
+                    }
+                    tail.add(new)
+                }
             }
         }
     }
 
+    /// a cell of its own with the car and cdr of this one
+    fn copy(&self) -> Cell<'c> {
+        Cell {
+            node: self.node().map(|node| {
+                Rc::new(UnsafeCell::new(Node {
+                    head: node.head.clone(),
+                    tail: node.tail.clone(),
+                    dotted: node.dotted.clone(),
+                }))
+            }),
+            quoted: self.quoted,
+        }
+    }
+
     pub fn pop(&mut self) -> bool {
-        if !self.tail.is_null() {
-            self.tail.drop_in_place();
-            self.tail = UniquePointer::null();
+        let node = match self.node.as_ref() {
+            Some(_) => self.node_mut(),
+            None => return false,
+        };
+        if node.tail.is_some() {
+            node.tail = None;
             true
-        } else if !self.head.is_null() {
-            self.head.drop_in_place();
-            self.head = UniquePointer::null();
+        } else if node.head.is_some() {
+            node.head = None;
             true
         } else {
             false
@@ -128,82 +236,100 @@ impl<'c> Cell<'c> {
     /// `O(n)`
     pub fn len(&self) -> usize {
         let mut len = 0;
-        if !self.head.is_null() {
-            len += 1
-        }
-        if let Some(tail) = self.tail() {
-            len += tail.len();
+        let mut cell = Some(self);
+        while let Some(current) = cell {
+            if current.head_ref().is_some() {
+                len += 1
+            }
+            cell = current.tail_ref();
         }
         len
     }
 
-    pub fn tail(&self) -> Option<&'c Cell<'c>> {
-        self.tail.as_ref()
+    pub fn tail(&self) -> Option<&Cell<'c>> {
+        self.tail_ref()
     }
 
     pub fn tail_mut(&mut self) -> Option<&'c mut Cell<'c>> {
-        self.tail.as_mut()
+        let tail = self.node_mut().tail.as_mut()?;
+        Some(unsafe { &mut *(tail as *mut Cell<'c>) })
     }
 
-    pub fn values(&self) -> Vec<Value<'c>> {
-        let mut values = Vec::<Value>::new();
-        if let Some(head) = self.head() {
-            values.push(head.clone());
-        }
-        if let Some(tail) = self.tail() {
-            values.extend(tail.values());
+    /// the cdr of this cell: its tail as a list, the value of a
+    /// dotted pair or nil
+    pub fn cdr(&self) -> Value<'c> {
+        match (self.next(), self.dotted_ref()) {
+            (Some(tail), _) => Value::list(tail.clone()),
+            (None, Some(dotted)) => dotted.clone(),
+            (None, None) => Value::Nil,
         }
-        values
     }
 
-    pub(crate) fn write(&mut self, value: Value<'c>) {
-        self.head.write(value);
-        self.incr_ref();
+    /// replaces the car of this cell, in every reference to it
+    pub fn set_car(&mut self, car: Value<'c>) {
+        self.node_mut().head = Some(car);
     }
 
-    pub(crate) fn swap_head(&mut self, other: &mut Self) {
-        self.head = unsafe {
-            let head = other.head.propagate();
-            other.head = self.head.propagate();
-            head
-        };
+    /// replaces the cdr of this cell, in every reference to it: lists
+    /// become its tail, shared rather than copied, and any other
+    /// value but nil makes this cell a dotted pair
+    pub fn set_cdr(&mut self, cdr: Value<'c>) {
+        let node = self.node_mut();
+        node.tail = None;
+        node.dotted = None;
+        match &cdr {
+            Value::List(tail) | Value::QuotedList(tail) if !tail.is_nil() =>
+                node.tail = Some(tail.clone()),
+            cdr if cdr.is_empty() => {},
+            _ => node.dotted = Some(cdr),
+        }
     }
 
-    pub(crate) fn swap_tail(&mut self, other: &mut Self) {
-        self.tail = unsafe {
-            let tail = other.tail.propagate();
-            other.tail = self.tail.propagate();
-            tail
-        };
+    /// replaces the cdr of the last cell of this list, see
+    /// [`set_cdr`](Cell::set_cdr)
+    pub fn set_last_cdr(&mut self, cdr: Value<'c>) {
+        match self.tail_mut().filter(|tail| !tail.is_nil()) {
+            Some(tail) => tail.set_last_cdr(cdr),
+            None => self.set_cdr(cdr),
+        }
     }
 
-    pub(crate) fn swap_refs(&mut self, other: &mut Self) {
-        self.refs = {
-            let refs = other.refs.clone();
-            other.refs = self.refs.clone();
-            refs
-        };
+    /// the value ending this list when it is improper, `None` for
+    /// proper lists
+    pub fn dotted(&self) -> Option<Value<'c>> {
+        match self.next() {
+            Some(tail) => tail.dotted(),
+            None => self.dotted_ref().cloned(),
+        }
     }
 
-    pub fn to_vec(&self) -> Vec<Value<'c>> {
-        Vec::<Value<'c>>::from_iter(self.clone().into_iter())
+    pub fn is_proper(&self) -> bool {
+        self.dotted().is_none()
     }
 
-    fn incr_ref(&mut self) {
-        self.refs.incr();
+    /// the tail of this cell unless it is empty
+    fn next(&self) -> Option<&Cell<'c>> {
+        self.tail().filter(|tail| !tail.is_nil())
     }
 
-    fn decr_ref(&mut self) {
-        self.refs.decr();
+    pub fn values(&self) -> Vec<Value<'c>> {
+        let mut values = Vec::<Value>::new();
+        let mut cell = Some(self);
+        while let Some(current) = cell {
+            if let Some(head) = current.head() {
+                values.push(head);
+            }
+            cell = current.tail();
+        }
+        values
     }
 
-    fn dealloc(&mut self) {
-        if self.refs > 0 {
-            self.decr_ref();
-        } else {
-            self.head.drop_in_place();
-            self.tail.drop_in_place();
-        }
+    pub(crate) fn write(&mut self, value: Value<'c>) {
+        self.node_mut().head = Some(value);
+    }
+
+    pub fn to_vec(&self) -> Vec<Value<'c>> {
+        Vec::<Value<'c>>::from_iter(self.clone().into_iter())
     }
 
     fn repr(&self) -> String {
@@ -215,18 +341,13 @@ impl<'c> Cell<'c> {
                     format!("null")
                 } else {
                     [
-                        if self.head.is_null() {
-                            format!("head: {}", "null")
-                        } else {
-                            format!("head={:#?}", self.head().unwrap_or_default())
+                        match self.head_ref() {
+                            None => format!("head: {}", "null"),
+                            Some(head) => format!("head={:#?}", head),
                         },
-                        if self.tail.is_null() {
-                            format!("tail: {}", "null")
-                        } else {
-                            format!(
-                                "tail={:#?}",
-                                self.tail().map(Clone::clone).unwrap_or_default()
-                            )
+                        match self.tail_ref() {
+                            None => format!("tail: {}", "null"),
+                            Some(tail) => format!("tail={:#?}", tail),
                         },
                     ]
                     .join(" | ")
@@ -244,6 +365,20 @@ impl<'c> Quotable for Cell<'c> {
     fn set_quoted(&mut self, quoted: bool) {
         self.quoted = quoted;
     }
+
+    /// a clone rather than the bitwise copy of [`Quotable::quote`] so
+    /// that the node is reference counted
+    fn quote(&self) -> Cell<'c> {
+        let mut cell = self.clone();
+        cell.set_quoted(true);
+        cell
+    }
+
+    fn unquote(&self) -> Cell<'c> {
+        let mut cell = self.clone();
+        cell.set_quoted(false);
+        cell
+    }
 }
 
 impl<'c, T: Quotable + AsCell<'c>, const N: usize> AsCell<'c> for [T; N] {
@@ -278,7 +413,7 @@ impl<'c> AsCell<'c> for Cell<'c> {
 }
 impl<'c> AsCell<'c> for &Cell<'c> {
     fn as_cell(&self) -> Cell<'c> {
-        UniquePointer::read_only(*self).read()
+        (*self).clone()
     }
 }
 
@@ -369,7 +504,7 @@ impl<'c> PartialEq<Cell<'c>> for Cell<'c> {
         }
         let slen = self.len();
         let olen = other.len();
-        if slen != olen {
+        if slen != olen || self.dotted() != other.dotted() {
             return false;
         }
 
@@ -400,33 +535,44 @@ impl<'c> Default for Cell<'c> {
     }
 }
 
-/// [`Clone`] implementation for [`Cell`] clones the internal
-/// pointers.
+/// [`Clone`] implementation for [`Cell`] shares its node.
 impl<'c> Clone for Cell<'c> {
     fn clone(&self) -> Cell<'c> {
-        let mut cell = Cell::nil();
-        cell.refs = self.refs.clone();
-        cell.incr_ref();
-        if self.head.is_not_null() {
-            cell.head = UniquePointer::from_ref(self.head.inner_ref())
+        Cell {
+            node: self.node.clone(),
+            quoted: false,
         }
-        if self.tail.is_not_null() {
-            cell.tail = UniquePointer::from_ref(self.tail.inner_ref())
-        }
-        cell
+    }
+}
+impl<'c> Eq for Cell<'c> {}
+impl<'c> PartialOrd for Cell<'c> {
+    fn partial_cmp(&self, other: &Cell<'c>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<'c> Ord for Cell<'c> {
+    fn cmp(&self, other: &Cell<'c>) -> Ordering {
+        self.values()
+            .cmp(&other.values())
+            .then_with(|| self.dotted().cmp(&other.dotted()))
     }
 }
 impl<'c> Hash for Cell<'c> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.head().hash(state);
         self.tail().hash(state);
-        self.refs.hash(state);
+        self.dotted_ref().hash(state);
         self.quoted.hash(state);
     }
 }
+/// frees the tails the cell holds the last reference to one at a
+/// time, so that dropping a long list does not recurse through it
 impl<'c> Drop for Cell<'c> {
     fn drop(&mut self) {
-        self.dealloc();
+        let mut node = self.node.take();
+        while let Some(last) = node.and_then(|node| Rc::try_unwrap(node).ok()) {
+            node = last.into_inner().tail.and_then(|mut tail| tail.node.take());
+        }
     }
 }
 
@@ -447,18 +593,14 @@ impl std::fmt::Display for Cell<'_> {
                 "".to_string()
             } else {
                 let mut parts = Vec::<String>::new();
-                if self.head.is_not_null() {
-                    parts.push(
-                        self.head()
-                            .map(|value| value.to_string())
-                            .unwrap_or_default(),
-                    )
+                if let Some(head) = self.head_ref() {
+                    parts.push(head.to_string())
                 }
 
-                if self.tail.is_not_null() {
-                    if let Some(tail) = self.tail() {
-                        parts.push(tail.to_string());
-                    }
+                if let Some(tail) = self.next() {
+                    parts.push(tail.to_string());
+                } else if let Some(dotted) = self.dotted_ref() {
+                    parts.push(format!(". {}", dotted));
                 }
                 parts.join(" ").trim().to_string()
             }
@@ -468,7 +610,7 @@ impl std::fmt::Display for Cell<'_> {
 
 impl<'c> AsValue<'c> for Cell<'c> {
     fn as_value(&self) -> Value<'c> {
-        if self.tail.is_null() {
+        if self.is_last() {
             match self.head() {
                 Some(head) => {
                     let is_quoted = head.is_quoted();
@@ -490,14 +632,12 @@ impl<'c> AsValue<'c> for Cell<'c> {
 }
 
 pub struct CellIterator<'c> {
-    cell: UniquePointer<Cell<'c>>,
+    cell: Option<Cell<'c>>,
 }
 
 impl<'c> CellIterator<'c> {
     pub fn new(cell: Cell<'c>) -> CellIterator<'c> {
-        CellIterator {
-            cell: UniquePointer::from_ref(&cell),
-        }
+        CellIterator { cell: Some(cell) }
     }
 
     pub fn item(&self) -> Option<&Cell<'c>> {
@@ -505,25 +645,16 @@ impl<'c> CellIterator<'c> {
     }
 
     pub fn tail(&self) -> Option<&Cell<'c>> {
-        if let Some(cell) = self.cell.as_ref() {
-            cell.tail()
-        } else {
-            None
-        }
+        self.cell.as_ref().and_then(Cell::tail)
     }
 }
 impl<'c> Iterator for CellIterator<'c> {
     type Item = Value<'c>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.cell.is_not_null() {
-            let value = self.cell.inner_ref().head();
-            let next_tail = self.cell.inner_ref().tail.clone();
-            self.cell = next_tail;
-            value
-        } else {
-            None
-        }
+        let cell = self.cell.take()?;
+        self.cell = cell.tail().cloned();
+        cell.head()
     }
 }
 
@@ -1,6 +1,5 @@
 #![allow(unused)]
 use sexprs_util::dbg;
-use unique_pointer::UniquePointer;
 
 use crate::{AsCell, AsValue, Cell, ListIterator, Quotable, Symbol, Value};
 pub fn list<'c, T: ListIterator<'c, Value<'c>>>(list: T) -> Value<'c> {
@@ -17,6 +16,14 @@ pub fn cons<'c, T: AsCell<'c>>(head: T, tail: &mut Cell<'c>) -> Cell<'c> {
     head.add(tail);
     head
 }
+/// a single cons cell of `car` and `cdr`, which is a dotted pair
+/// unless `cdr` is a list
+pub fn pair<'c>(car: Value<'c>, cdr: Value<'c>) -> Value<'c> {
+    let mut cell = Cell::nil();
+    cell.push_value(car);
+    cell.set_cdr(cdr);
+    Value::list(cell)
+}
 pub fn car<'c>(value: &Value<'c>) -> Value<'c> {
     let is_quoted = value.is_quoted();
     let value = match value {
@@ -32,17 +39,20 @@ pub fn car<'c>(value: &Value<'c>) -> Value<'c> {
 
 pub fn cdr<'c>(item: &Value<'c>) -> Value<'c> {
     match item {
-        Value::List(ref h) | Value::QuotedList(ref h) => h
-            .tail()
-            .map(|cell| Value::list(cell.clone()))
-            .unwrap_or_default(),
+        Value::List(ref h) | Value::QuotedList(ref h) => h.cdr(),
         _ => Value::Nil,
     }
 }
+/// concatenates the lists in `list`: every argument but the last is
+/// copied, dropping the cdr ending an improper one, and the last
+/// becomes the cdr of the result as is, so that `(append '(1) 2)` is
+/// `(1 . 2)`
 pub fn append<'c, T: ListIterator<'c, Value<'c>>>(list: T) -> Value<'c> {
     let is_quoted = list.is_quoted();
+    let mut values = list.into_iter().collect::<Vec<Value<'c>>>();
+    let last = values.pop().unwrap_or_default();
     let mut items = Cell::nil();
-    for value in list {
+    for value in values {
         match &value {
             Value::List(h) | Value::QuotedList(h) =>
                 for item in h.clone().into_iter() {
@@ -54,6 +64,10 @@ pub fn append<'c, T: ListIterator<'c, Value<'c>>>(list: T) -> Value<'c> {
             },
         }
     }
+    if items.is_nil() {
+        return last;
+    }
+    items.set_last_cdr(last);
     if is_quoted {
         Value::quoted_list(items)
     } else {
@@ -65,8 +79,8 @@ pub fn makelist<'c>(value: Value<'c>, count: usize) -> Value<'c> {
 }
 
 pub fn setcar<'c>(cell: &mut Cell<'c>, value: &Value<'c>) {
-    cell.head.write_ref(value);
+    cell.set_car(value.clone());
 }
 pub fn setcdr<'c>(cell: &mut Cell<'c>, new_tail: &Cell<'c>) {
-    cell.tail.write_ref(new_tail);
+    cell.set_cdr(Value::List(new_tail.clone()));
 }
@@ -2,11 +2,11 @@
 pub mod traits;
 pub use traits::{AsNumber, ListValue, Quotable};
 pub mod cons;
-pub use cons::{append, car, cdr, list, cons, makelist, setcar, setcdr};
+pub use cons::{append, car, cdr, list, cons, makelist, pair, setcar, setcdr};
 pub mod cell;
-pub use cell::{AsCell, Cell, ListIterator};
+pub use cell::{AsCell, Cell, ListIterator, WeakCell};
 pub mod value;
-pub use value::{AsValue, Float, Integer, UnsignedInteger, Value, AsFloat, AsInteger, AsUnsignedInteger, ValueIterator};
+pub use value::{AsValue, BigInt, BigRational, Float, Integer, UnsignedInteger, Value, AsFloat, AsInteger, AsUnsignedInteger, ValueIterator, Vector, HashTable, HashTableTest, Callable, Procedure};
 pub mod symbol;
 pub use symbol::{AsSymbol, Symbol};
 pub mod macros;
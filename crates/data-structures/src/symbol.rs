@@ -1,8 +1,13 @@
 #![allow(unused)]
 use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
 
-use unique_pointer::UniquePointer;
 
 use crate::{AsValue, Quotable, Value};
 
@@ -13,38 +18,98 @@ pub trait AsSymbol<'c> {
     }
 }
 
-#[derive(Clone, PartialOrd, Ord, Default, Eq, Hash)]
+thread_local! {
+    /// the names of the symbols in use, each stored once so that
+    /// symbols are compared by address
+    static SYMBOLS: RefCell<Interner> = RefCell::new(Interner::default());
+}
+
+/// the set of symbol names, pruned of the names no symbol refers to
+/// any longer whenever it has doubled in size since the last pruning
+#[derive(Default)]
+struct Interner {
+    names: HashSet<Rc<str>>,
+    capacity: usize,
+}
+
+impl Interner {
+    fn intern(&mut self, name: &str) -> Rc<str> {
+        if let Some(name) = self.names.get(name) {
+            return name.clone();
+        }
+        if self.names.len() >= self.capacity {
+            self.names.retain(|name| Rc::strong_count(name) > 1);
+            self.capacity = (self.names.len() * 2).max(1024);
+        }
+        let name = Rc::<str>::from(name);
+        self.names.insert(name.clone());
+        name
+    }
+}
+
+/// the interned copy of `name`
+fn intern(name: &str) -> Rc<str> {
+    SYMBOLS.with(|symbols| symbols.borrow_mut().intern(name))
+}
+
+/// a symbol name, interned so that equal names share their storage:
+/// comparing and hashing symbols only looks at the address of their
+/// name, and creating a symbol allocates only the first time its name
+/// is seen. Names are released once no symbol refers to them.
+#[derive(Clone, Eq)]
 pub struct Symbol<'c> {
-    sym: &'c str,
+    sym: Rc<str>,
     quoted: bool,
+    lifetime: PhantomData<&'c str>,
 }
 impl<'c> Symbol<'c> {
-    pub fn new<T: ToString>(sym: T) -> Symbol<'c> {
+    pub fn new<T: AsRef<str>>(sym: T) -> Symbol<'c> {
         Symbol::quoted(sym, false)
     }
 
-    pub fn quoted<T: ToString>(sym: T, quoted: bool) -> Symbol<'c> {
+    pub fn quoted<T: AsRef<str>>(sym: T, quoted: bool) -> Symbol<'c> {
         Symbol {
-            sym: sym.to_string().leak(),
+            sym: intern(sym.as_ref()),
             quoted,
+            lifetime: PhantomData,
         }
     }
 
-    pub fn symbol(&self) -> &'c str {
-        self.sym
+    /// the number of symbol names interned on the current thread,
+    /// including names no longer in use that were not pruned yet
+    pub fn interned() -> usize {
+        SYMBOLS.with(|symbols| symbols.borrow().names.len())
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.sym
     }
 
     pub fn quote(&self) -> Symbol<'c> {
-        Symbol::quoted(self.symbol(), true)
+        Symbol {
+            sym: self.sym.clone(),
+            quoted: true,
+            lifetime: PhantomData,
+        }
     }
 
     pub fn unquote(&self) -> Symbol<'c> {
-        Symbol::quoted(self.symbol(), false)
+        Symbol {
+            sym: self.sym.clone(),
+            quoted: false,
+            lifetime: PhantomData,
+        }
     }
 
     pub fn is_quoted(&self) -> bool {
         self.quoted
     }
+
+    /// true for symbols such as `:test` which start with a colon and
+    /// evaluate to themselves
+    pub fn is_keyword(&self) -> bool {
+        self.sym.len() > 1 && self.sym.starts_with(':')
+    }
 }
 
 impl Display for Symbol<'_> {
@@ -77,7 +142,7 @@ impl<'c> AsSymbol<'c> for Symbol<'c> {
 }
 impl<'c> AsSymbol<'c> for &Symbol<'c> {
     fn as_symbol(&self) -> Symbol<'c> {
-        UniquePointer::read_only(*self).read()
+        (*self).clone()
     }
 }
 
@@ -117,6 +182,16 @@ impl<'c> Quotable for Symbol<'c> {
     fn set_quoted(&mut self, quoted: bool) {
         self.quoted = quoted;
     }
+
+    /// a clone rather than the bitwise copy of [`Quotable::quote`] so
+    /// that names are reference counted
+    fn quote(&self) -> Symbol<'c> {
+        Symbol::quote(self)
+    }
+
+    fn unquote(&self) -> Symbol<'c> {
+        Symbol::unquote(self)
+    }
 }
 impl<'c> AsValue<'c> for Symbol<'c> {
     fn as_value(&self) -> Value<'c> {
@@ -126,7 +201,31 @@ impl<'c> AsValue<'c> for Symbol<'c> {
 
 impl<'c> std::cmp::PartialEq for Symbol<'c> {
     fn eq(&self, rhs: &Symbol<'c>) -> bool {
-        self.symbol() == rhs.symbol()
+        Rc::ptr_eq(&self.sym, &rhs.sym)
+    }
+}
+
+impl<'c> Hash for Symbol<'c> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sym.as_ptr().hash(state);
+    }
+}
+
+impl<'c> Ord for Symbol<'c> {
+    fn cmp(&self, rhs: &Symbol<'c>) -> Ordering {
+        self.sym.cmp(&rhs.sym)
+    }
+}
+
+impl<'c> PartialOrd for Symbol<'c> {
+    fn partial_cmp(&self, rhs: &Symbol<'c>) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+
+impl<'c> Default for Symbol<'c> {
+    fn default() -> Symbol<'c> {
+        Symbol::new("")
     }
 }
 
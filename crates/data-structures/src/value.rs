@@ -3,8 +3,8 @@ use std::borrow::Cow;
 use std::convert::{AsMut, AsRef};
 use std::fmt::{Debug, Display, Formatter};
 use std::iter::{Extend, FromIterator, IntoIterator};
+use std::rc::Rc;
 
-use unique_pointer::UniquePointer;
 
 pub mod integer;
 mod macros;
@@ -12,6 +12,16 @@ pub use integer::{AsInteger, Integer};
 pub mod float;
 pub use float::{AsFloat, Float};
 pub mod unsigned_integer;
+pub mod big_integer;
+pub use big_integer::BigInt;
+pub mod ratio;
+pub use ratio::BigRational;
+pub mod vector;
+pub use vector::Vector;
+pub mod hash_table;
+pub use hash_table::{HashTable, HashTableTest};
+pub mod procedure;
+pub use procedure::{Callable, Procedure};
 use sexprs_util::{dbg, try_result};
 pub use unsigned_integer::{AsUnsignedInteger, UnsignedInteger};
 
@@ -29,13 +39,20 @@ pub enum Value<'c> {
     #[default]
     Nil,
     T,
-    String(&'c str),
+    String(Rc<str>),
+    Char(char),
     Symbol(Symbol<'c>),
     QuotedSymbol(Symbol<'c>),
     Byte(u8),
     UnsignedInteger(UnsignedInteger),
     Integer(Integer),
     Float(Float),
+    BigInt(BigInt),
+    Ratio(BigRational),
+    Vector(Vector<Value<'c>>),
+    Bytes(Vector<u8>),
+    HashTable(HashTable<'c>),
+    Procedure(Procedure<'c>),
     List(Cell<'c>),
     QuotedList(Cell<'c>),
     EmptyList,
@@ -59,7 +76,11 @@ impl<'c> Value<'c> {
     }
 
     pub fn string<T: ToString>(value: T) -> Value<'c> {
-        Value::String(value.to_string().leak())
+        Value::String(Rc::from(value.to_string()))
+    }
+
+    pub fn char(char: char) -> Value<'c> {
+        Value::Char(char)
     }
 
     pub fn byte<T: AsNumber<u8>>(byte: T) -> Value<'c> {
@@ -78,6 +99,24 @@ impl<'c> Value<'c> {
         Value::Float(value.as_float())
     }
 
+    pub fn big_integer<T: Into<BigInt>>(value: T) -> Value<'c> {
+        Value::BigInt(value.into())
+    }
+
+    /// the ratio `numerator/denominator` reduced to lowest terms,
+    /// panics when `denominator` is zero
+    pub fn ratio<N: Into<BigInt>, D: Into<BigInt>>(numerator: N, denominator: D) -> Value<'c> {
+        Value::Ratio(BigRational::new(numerator.into(), denominator.into()))
+    }
+
+    pub fn vector<T: IntoIterator<Item = Value<'c>>>(items: T) -> Value<'c> {
+        Value::Vector(Vector::new(items.into_iter().collect()))
+    }
+
+    pub fn bytes<T: Into<Vec<u8>>>(bytes: T) -> Value<'c> {
+        Value::Bytes(Vector::new(bytes.into()))
+    }
+
     pub fn list<T: AsCell<'c>>(item: T) -> Value<'c> {
         if item.is_quoted() {
             Value::QuotedList(item.as_cell().quote())
@@ -173,7 +212,7 @@ impl<'c> Value<'c> {
     pub fn unwrap_list(&self) -> Value<'c> {
         match self {
             Value::List(cell) | Value::QuotedList(cell) => {
-                if cell.tail.is_null() {
+                if cell.is_last() {
                     let value = cell.head().unwrap_or_default();
                     value.clone()
                 } else {
@@ -205,6 +244,32 @@ impl<'c> Value<'c> {
         }
     }
 
+    pub fn is_big_integer(&self) -> bool {
+        match self {
+            Value::BigInt(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_ratio(&self) -> bool {
+        match self {
+            Value::Ratio(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        match self {
+            Value::Byte(_)
+            | Value::UnsignedInteger(_)
+            | Value::Integer(_)
+            | Value::Float(_)
+            | Value::BigInt(_)
+            | Value::Ratio(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn is_string(&self) -> bool {
         match self {
             Value::String(_) => true,
@@ -212,6 +277,34 @@ impl<'c> Value<'c> {
         }
     }
 
+    pub fn is_char(&self) -> bool {
+        match self {
+            Value::Char(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_vector(&self) -> bool {
+        match self {
+            Value::Vector(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_bytes(&self) -> bool {
+        match self {
+            Value::Bytes(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_hash_table(&self) -> bool {
+        match self {
+            Value::HashTable(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn is_symbol(&self) -> bool {
         match self {
             Value::Symbol(_) => true,
@@ -227,6 +320,72 @@ impl<'c> Value<'c> {
             _ => false,
         }
     }
+
+    /// identity: nil and the empty lists are all `eq`, as are symbols
+    /// of the same name and characters, bytes, unsigned integers and
+    /// integers of the same type and value. Lists are `eq` when they
+    /// are the same [`Cell`](Cell::is_same) and strings, vectors,
+    /// bytes, hash tables and procedures when they are clones of the
+    /// same one. Floats, big integers and ratios are boxed and never
+    /// `eq`.
+    pub fn is_eq(&self, other: &Value<'c>) -> bool {
+        match (self, other) {
+            (Value::T, Value::T) => true,
+            (Value::Symbol(lhs) | Value::QuotedSymbol(lhs), Value::Symbol(rhs) | Value::QuotedSymbol(rhs)) =>
+                lhs.symbol() == rhs.symbol(),
+            (Value::Byte(lhs), Value::Byte(rhs)) => lhs == rhs,
+            (Value::Char(lhs), Value::Char(rhs)) => lhs == rhs,
+            (Value::UnsignedInteger(lhs), Value::UnsignedInteger(rhs)) => lhs == rhs,
+            (Value::Integer(lhs), Value::Integer(rhs)) => lhs == rhs,
+            (Value::String(lhs), Value::String(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Vector(lhs), Value::Vector(rhs)) => lhs.is_same(rhs),
+            (Value::Bytes(lhs), Value::Bytes(rhs)) => lhs.is_same(rhs),
+            (Value::HashTable(lhs), Value::HashTable(rhs)) => lhs.is_same(rhs),
+            (Value::Procedure(lhs), Value::Procedure(rhs)) => lhs.is_same(rhs),
+            (Value::List(lhs) | Value::QuotedList(lhs), Value::List(rhs) | Value::QuotedList(rhs)) =>
+                lhs.is_same(rhs),
+            (lhs, rhs) => lhs.is_empty() && rhs.is_empty(),
+        }
+    }
+
+    /// [`is_eq`](Value::is_eq), or numbers of the same type and
+    /// value. Floats are compared by their bits, so `0.0` and `-0.0`
+    /// are not `eql` while a NaN is `eql` to itself.
+    pub fn is_eql(&self, other: &Value<'c>) -> bool {
+        match (self, other) {
+            (Value::Float(lhs), Value::Float(rhs)) => lhs.to_bits() == rhs.to_bits(),
+            (Value::BigInt(lhs), Value::BigInt(rhs)) => lhs == rhs,
+            (Value::Ratio(lhs), Value::Ratio(rhs)) => lhs == rhs,
+            (lhs, rhs) => lhs.is_eq(rhs),
+        }
+    }
+
+    /// structural equality: strings and bytes with the same contents,
+    /// vectors whose elements are `equal` and lists whose elements and
+    /// dotted cdrs are `equal`, whether quoted or not, otherwise
+    /// [`is_eql`](Value::is_eql)
+    pub fn is_equal(&self, other: &Value<'c>) -> bool {
+        match (self, other) {
+            (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
+            (Value::Bytes(lhs), Value::Bytes(rhs)) => lhs == rhs,
+            (Value::Vector(lhs), Value::Vector(rhs)) =>
+                lhs.len() == rhs.len()
+                    && lhs.to_vec().iter().zip(rhs.to_vec().iter()).all(|(lhs, rhs)| lhs.is_equal(rhs)),
+            (Value::List(lhs) | Value::QuotedList(lhs), Value::List(rhs) | Value::QuotedList(rhs))
+                if !lhs.is_nil() || !rhs.is_nil() =>
+                lhs.len() == rhs.len()
+                    && match (lhs.dotted(), rhs.dotted()) {
+                        (Some(lhs), Some(rhs)) => lhs.is_equal(&rhs),
+                        (lhs, rhs) => lhs.is_none() && rhs.is_none(),
+                    }
+                    && lhs
+                        .clone()
+                        .into_iter()
+                        .zip(rhs.clone())
+                        .all(|(lhs, rhs)| lhs.is_equal(&rhs)),
+            (lhs, rhs) => lhs.is_eql(rhs),
+        }
+    }
 }
 
 impl<'c> AsValue<'c> for Value<'c> {
@@ -236,7 +395,7 @@ impl<'c> AsValue<'c> for Value<'c> {
 }
 impl<'c> AsValue<'c> for &Value<'c> {
     fn as_value(&self) -> Value<'c> {
-        UniquePointer::read_only(*self).read()
+        (*self).clone()
     }
 }
 
@@ -261,9 +420,30 @@ impl Display for Value<'_> {
                 Value::Float(h) => format!("{}", h),
                 Value::Integer(h) => format!("{}", h),
                 Value::String(h) => format!("{:#?}", h),
+                Value::Char(h) => format!("?{}", char_literal(*h)),
                 Value::Symbol(h) => format!("{}", h),
                 Value::QuotedSymbol(h) => format!("'{}", h),
                 Value::UnsignedInteger(h) => format!("{}", h),
+                Value::BigInt(h) => format!("{}", h),
+                Value::Ratio(h) => format!("{}", h),
+                Value::Vector(h) => format!(
+                    "[{}]",
+                    h.to_vec().iter().map(|item| item.to_string()).collect::<Vec<String>>().join(" ")
+                ),
+                Value::Bytes(h) => format!(
+                    "#u8({})",
+                    h.to_vec().iter().map(|byte| byte.to_string()).collect::<Vec<String>>().join(" ")
+                ),
+                Value::HashTable(h) => format!(
+                    "#s(hash-table test {} data ({}))",
+                    h.test().name(),
+                    h.entries()
+                        .iter()
+                        .map(|(key, value)| format!("{} {}", key, value))
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                ),
+                Value::Procedure(h) => format!("{}", h),
                 Value::List(h) => {
                     if h.is_nil() {
                         format!("()")
@@ -308,6 +488,12 @@ impl<'c> From<bool> for Value<'c> {
         }
     }
 }
+impl<'c> From<char> for Value<'c> {
+    fn from(value: char) -> Value<'c> {
+        Value::Char(value)
+    }
+}
+
 impl<'c> From<u8> for Value<'c> {
     fn from(value: u8) -> Value<'c> {
         Value::Byte(value)
@@ -333,7 +519,7 @@ impl<'c> From<&Symbol<'c>> for Value<'c> {
 }
 impl<'c> From<&'c str> for Value<'c> {
     fn from(value: &'c str) -> Value<'c> {
-        Value::String(value.to_string().leak())
+        Value::string(value)
     }
 }
 impl<'c> From<u64> for Value<'c> {
@@ -355,19 +541,30 @@ impl<'c> From<i32> for Value<'c> {
     }
 }
 
+impl<'c> From<BigInt> for Value<'c> {
+    fn from(value: BigInt) -> Value<'c> {
+        Value::BigInt(value)
+    }
+}
+impl<'c> From<BigRational> for Value<'c> {
+    fn from(value: BigRational) -> Value<'c> {
+        Value::Ratio(value)
+    }
+}
+
 impl<'c> From<Cow<'c, str>> for Value<'c> {
     fn from(value: Cow<'c, str>) -> Value<'c> {
-        Value::from(value.into_owned().to_string().leak())
+        Value::string(value)
     }
 }
 impl<'c> From<&'c mut str> for Value<'c> {
     fn from(value: &'c mut str) -> Value<'c> {
-        Value::String(value.to_string().leak())
+        Value::string(value)
     }
 }
 impl<'c> From<String> for Value<'c> {
     fn from(value: String) -> Value<'c> {
-        Value::String(value.leak())
+        Value::String(Rc::from(value))
     }
 }
 impl<'c> From<Option<String>> for Value<'c> {
@@ -464,7 +661,7 @@ impl<'c> AsValue<'c> for u8 {
 // }
 impl<'c> AsValue<'c> for &'c str {
     fn as_value(&self) -> Value<'c> {
-        Value::String(self.to_string().leak())
+        Value::string(self)
     }
 }
 impl<'c> AsValue<'c> for u64 {
@@ -488,17 +685,17 @@ impl<'c> AsValue<'c> for i32 {
 
 impl<'c> AsValue<'c> for Cow<'c, str> {
     fn as_value(&self) -> Value<'c> {
-        Value::from(self.clone().into_owned().to_string().leak())
+        Value::string(self)
     }
 }
 impl<'c> AsValue<'c> for &'c mut str {
     fn as_value(&self) -> Value<'c> {
-        Value::String(self.to_string().leak())
+        Value::string(self)
     }
 }
 impl<'c> AsValue<'c> for String {
     fn as_value(&self) -> Value<'c> {
-        Value::String(self.to_string().leak())
+        Value::string(self)
     }
 }
 impl<'c> AsValue<'c> for Option<String> {
@@ -535,8 +732,7 @@ impl<'c> AsCell<'c> for Value<'c> {
 }
 impl<'c> AsCell<'c> for &Value<'c> {
     fn as_cell(&self) -> Cell<'c> {
-        let value = UniquePointer::read_only(*self).read();
-        value.as_cell()
+        (*self).as_cell()
     }
 }
 
@@ -559,14 +755,14 @@ impl<'c> ListIterator<'c, Value<'c>> for &Value<'c> {
 
 #[derive(Debug, Clone)]
 pub struct ValueIterator<'c> {
-    cell: UniquePointer<Cell<'c>>,
+    cell: Option<Cell<'c>>,
     quoted: bool,
 }
 
 impl<'c> ValueIterator<'c> {
     pub fn new(cell: &Cell<'c>, quoted: bool) -> ValueIterator<'c> {
         ValueIterator {
-            cell: UniquePointer::from_ref(cell),
+            cell: Some(cell.clone()),
             quoted,
         }
     }
@@ -576,25 +772,16 @@ impl<'c> ValueIterator<'c> {
     }
 
     pub fn tail(&self) -> Option<&Cell<'c>> {
-        if let Some(cell) = self.cell.as_ref() {
-            cell.tail()
-        } else {
-            None
-        }
+        self.cell.as_ref().and_then(Cell::tail)
     }
 }
 impl<'c> Iterator for ValueIterator<'c> {
     type Item = Value<'c>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.cell.is_not_null() {
-            let value = self.cell.inner_ref().head();
-            let next_tail = self.cell.inner_ref().tail.clone();
-            self.cell = next_tail;
-            value
-        } else {
-            None
-        }
+        let cell = self.cell.take()?;
+        self.cell = cell.tail().cloned();
+        cell.head()
     }
 }
 impl<'c> Quotable for ValueIterator<'c> {
@@ -733,6 +920,20 @@ impl<'c> Quotable for Value<'c> {
         }
     }
 
+    /// a clone rather than the bitwise copy of [`Quotable::quote`] so
+    /// that strings are reference counted
+    fn quote(&self) -> Value<'c> {
+        let mut value = self.clone();
+        value.set_quoted(true);
+        value
+    }
+
+    fn unquote(&self) -> Value<'c> {
+        let mut value = self.clone();
+        value.set_quoted(false);
+        value
+    }
+
     fn is_quoted(&self) -> bool {
         match self {
             Value::Symbol(h) => false,
@@ -787,6 +988,11 @@ impl<'c> AsFloat for Value<'c> {
     fn as_float(&self) -> Float {
         match self {
             Value::Float(float) => *float,
+            Value::Byte(byte) => Float::from(f64::from(*byte)),
+            Value::UnsignedInteger(value) => Float::from(f64::from(**value)),
+            Value::Integer(value) => Float::from(**value as f64),
+            Value::BigInt(value) => value.as_float(),
+            Value::Ratio(value) => value.as_float(),
             value => {
                 panic!("cannot convert {:#?} to float", self)
             }
@@ -798,6 +1004,10 @@ impl<'c> AsInteger for Value<'c> {
     fn as_integer(&self) -> Integer {
         match self {
             Value::Integer(integer) => *integer,
+            Value::Byte(byte) => Integer::from(i64::from(*byte)),
+            Value::UnsignedInteger(value) => Integer::from(i64::from(**value)),
+            Value::BigInt(value) => value.as_integer(),
+            Value::Ratio(value) => value.as_integer(),
             value => {
                 panic!("cannot convert {:#?} to integer", self)
             }
@@ -930,3 +1140,17 @@ impl<'c> AsNumber<u32> for Value<'c> {
 //         false
 //     }
 // }
+
+/// `char` as read after `?`, with whitespace, control characters and
+/// delimiters escaped
+fn char_literal(char: char) -> String {
+    match char {
+        ' ' => "\\s".to_string(),
+        '\n' => "\\n".to_string(),
+        '\t' => "\\t".to_string(),
+        '\r' => "\\r".to_string(),
+        '\\' | '(' | ')' | '"' | '\'' | '`' | ',' | ';' => format!("\\{}", char),
+        char if char.is_control() => format!("\\u{{{:x}}}", u32::from(char)),
+        char => char.to_string(),
+    }
+}
@@ -0,0 +1,238 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use crate::Value;
+
+/// the predicate a [`HashTable`] compares its keys with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HashTableTest {
+    Eq,
+    #[default]
+    Eql,
+    Equal,
+}
+
+impl HashTableTest {
+    pub fn from_name(name: &str) -> Option<HashTableTest> {
+        match name {
+            "eq" => Some(HashTableTest::Eq),
+            "eql" => Some(HashTableTest::Eql),
+            "equal" => Some(HashTableTest::Equal),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashTableTest::Eq => "eq",
+            HashTableTest::Eql => "eql",
+            HashTableTest::Equal => "equal",
+        }
+    }
+
+    pub fn test<'c>(&self, lhs: &Value<'c>, rhs: &Value<'c>) -> bool {
+        match self {
+            HashTableTest::Eq => lhs.is_eq(rhs),
+            HashTableTest::Eql => lhs.is_eql(rhs),
+            HashTableTest::Equal => lhs.is_equal(rhs),
+        }
+    }
+}
+
+/// a mutable map from keys compared with a [`HashTableTest`] to
+/// values, which remembers the order keys were first inserted in.
+///
+/// Like [`Vector`](crate::Vector), clones share their entries, while
+/// comparison and hashing look at the test and entries only.
+#[derive(Clone, Default)]
+pub struct HashTable<'c> {
+    test: HashTableTest,
+    entries: Rc<RefCell<Entries<'c>>>,
+}
+
+#[derive(Default)]
+struct Entries<'c> {
+    items: Vec<(Value<'c>, Value<'c>)>,
+    index: HashMap<Key<'c>, usize>,
+}
+
+impl<'c> HashTable<'c> {
+    pub fn new(test: HashTableTest) -> HashTable<'c> {
+        HashTable {
+            test,
+            entries: Rc::default(),
+        }
+    }
+
+    pub fn test(&self) -> HashTableTest {
+        self.test
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.borrow().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().items.is_empty()
+    }
+
+    pub fn get(&self, key: &Value<'c>) -> Option<Value<'c>> {
+        let entries = self.entries.borrow();
+        entries
+            .index
+            .get(&self.key(key))
+            .map(|position| entries.items[*position].1.clone())
+    }
+
+    /// associates `value` with `key`, keeping the position of a key
+    /// already in the table
+    pub fn insert(&self, key: Value<'c>, value: Value<'c>) {
+        let mut entries = self.entries.borrow_mut();
+        match entries.index.get(&self.key(&key)).copied() {
+            Some(position) => entries.items[position].1 = value,
+            None => {
+                let position = entries.items.len();
+                entries.index.insert(self.key(&key), position);
+                entries.items.push((key, value));
+            },
+        }
+    }
+
+    /// removes `key` from the table, false when it was not there.
+    /// Takes time proportional to the number of entries inserted
+    /// after it.
+    pub fn remove(&self, key: &Value<'c>) -> bool {
+        let mut entries = self.entries.borrow_mut();
+        let position = match entries.index.remove(&self.key(key)) {
+            Some(position) => position,
+            None => return false,
+        };
+        entries.items.remove(position);
+        for later in entries.index.values_mut() {
+            if *later > position {
+                *later -= 1;
+            }
+        }
+        true
+    }
+
+    pub fn keys(&self) -> Vec<Value<'c>> {
+        self.entries.borrow().items.iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    /// the keys and values in insertion order
+    pub fn entries(&self) -> Vec<(Value<'c>, Value<'c>)> {
+        self.entries.borrow().items.clone()
+    }
+
+    /// true when `self` and `other` are clones of the same table
+    pub fn is_same(&self, other: &HashTable<'c>) -> bool {
+        Rc::ptr_eq(&self.entries, &other.entries)
+    }
+
+    fn key(&self, value: &Value<'c>) -> Key<'c> {
+        Key {
+            value: value.clone(),
+            test: self.test,
+        }
+    }
+}
+
+impl<'c> PartialEq for HashTable<'c> {
+    fn eq(&self, other: &HashTable<'c>) -> bool {
+        self.test == other.test && self.entries() == other.entries()
+    }
+}
+
+impl<'c> Eq for HashTable<'c> {}
+
+impl<'c> PartialOrd for HashTable<'c> {
+    fn partial_cmp(&self, other: &HashTable<'c>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'c> Ord for HashTable<'c> {
+    fn cmp(&self, other: &HashTable<'c>) -> Ordering {
+        self.test
+            .cmp(&other.test)
+            .then_with(|| self.entries().cmp(&other.entries()))
+    }
+}
+
+impl<'c> Hash for HashTable<'c> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.test.hash(state);
+        self.entries().hash(state);
+    }
+}
+
+impl<'c> Debug for HashTable<'c> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", Value::HashTable(self.clone()))
+    }
+}
+
+/// a key of [`Entries::index`], hashed consistently with its test
+struct Key<'c> {
+    value: Value<'c>,
+    test: HashTableTest,
+}
+
+impl<'c> PartialEq for Key<'c> {
+    fn eq(&self, other: &Key<'c>) -> bool {
+        self.test.test(&self.value, &other.value)
+    }
+}
+
+impl<'c> Eq for Key<'c> {}
+
+impl<'c> Hash for Key<'c> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_value(&self.value, self.test == HashTableTest::Equal, state)
+    }
+}
+
+/// hashes `value` by contents when `equal` and by identity otherwise,
+/// so that values which pass a test always hash the same
+fn hash_value<H: Hasher>(value: &Value, equal: bool, state: &mut H) {
+    if value.is_empty() {
+        return 0u8.hash(state);
+    }
+    match value {
+        Value::T => 1u8.hash(state),
+        Value::Symbol(symbol) | Value::QuotedSymbol(symbol) => (2u8, symbol.symbol()).hash(state),
+        Value::Char(char) => (3u8, char).hash(state),
+        Value::Byte(byte) => (4u8, byte).hash(state),
+        Value::UnsignedInteger(integer) => (5u8, integer).hash(state),
+        Value::Integer(integer) => (6u8, integer).hash(state),
+        Value::Float(float) => (7u8, float).hash(state),
+        Value::BigInt(integer) => (8u8, integer).hash(state),
+        Value::Ratio(ratio) => (9u8, ratio).hash(state),
+        Value::String(string) if equal => (10u8, string).hash(state),
+        Value::String(string) => (10u8, Rc::as_ptr(string) as *const () as usize).hash(state),
+        Value::Bytes(bytes) if equal => (11u8, bytes).hash(state),
+        Value::Bytes(bytes) => (11u8, bytes.identity()).hash(state),
+        Value::Vector(vector) if equal => {
+            12u8.hash(state);
+            for item in vector.to_vec() {
+                hash_value(&item, equal, state);
+            }
+        },
+        Value::Vector(vector) => (12u8, vector.identity()).hash(state),
+        Value::List(_) | Value::QuotedList(_) if equal => {
+            13u8.hash(state);
+            for item in value.values() {
+                hash_value(&item, equal, state);
+            }
+        },
+        Value::List(cell) | Value::QuotedList(cell) => (13u8, cell.identity()).hash(state),
+        Value::HashTable(table) => (14u8, Rc::as_ptr(&table.entries) as usize).hash(state),
+        Value::Procedure(procedure) => (15u8, procedure.identity()).hash(state),
+        _ => 0u8.hash(state),
+    }
+}
@@ -0,0 +1,101 @@
+use std::any::TypeId;
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// a function defined outside of this crate, such as a closure of the
+/// virtual machine, which a [`Procedure`] can hold.
+///
+/// # Safety
+///
+/// [`static_type_id`](Callable::static_type_id) and
+/// [`callable_type_id`](Callable::callable_type_id) must both return
+/// the [`TypeId`] of the implementing type with `'static` lifetimes,
+/// and no other type may return it, since
+/// [`Procedure::downcast_ref`] casts a procedure to the type its id
+/// identifies.
+pub unsafe trait Callable<'c>: Display {
+    fn static_type_id() -> TypeId
+    where
+        Self: Sized;
+
+    fn callable_type_id(&self) -> TypeId;
+}
+
+/// a function as a value, such as the closure made by `lambda`.
+///
+/// Clones share the function, and comparison and hashing look at
+/// its identity only.
+#[derive(Clone)]
+pub struct Procedure<'c> {
+    callable: Rc<dyn Callable<'c> + 'c>,
+}
+
+impl<'c> Procedure<'c> {
+    pub fn new<T: Callable<'c> + 'c>(callable: T) -> Procedure<'c> {
+        Procedure {
+            callable: Rc::new(callable),
+        }
+    }
+
+    /// the function held when it is a `T`
+    pub fn downcast_ref<T: Callable<'c> + 'c>(&self) -> Option<&T> {
+        if self.callable.callable_type_id() == T::static_type_id() {
+            // SAFETY: implementations of `Callable` return ids unique
+            // to their type, so the callable is a `T`
+            Some(unsafe { &*(Rc::as_ptr(&self.callable) as *const T) })
+        } else {
+            None
+        }
+    }
+
+    /// true when `self` and `other` are clones of the same procedure
+    pub fn is_same(&self, other: &Procedure<'c>) -> bool {
+        self.identity() == other.identity()
+    }
+
+    /// an address that identifies the procedure the way
+    /// [`is_same`](Procedure::is_same) does, for hashing by identity
+    pub(crate) fn identity(&self) -> usize {
+        Rc::as_ptr(&self.callable) as *const () as usize
+    }
+}
+
+impl<'c> PartialEq for Procedure<'c> {
+    fn eq(&self, other: &Procedure<'c>) -> bool {
+        self.is_same(other)
+    }
+}
+
+impl<'c> Eq for Procedure<'c> {}
+
+impl<'c> PartialOrd for Procedure<'c> {
+    fn partial_cmp(&self, other: &Procedure<'c>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'c> Ord for Procedure<'c> {
+    fn cmp(&self, other: &Procedure<'c>) -> Ordering {
+        self.identity().cmp(&other.identity())
+    }
+}
+
+impl<'c> Hash for Procedure<'c> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.identity().hash(state)
+    }
+}
+
+impl<'c> Display for Procedure<'c> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.callable)
+    }
+}
+
+impl<'c> Debug for Procedure<'c> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.callable)
+    }
+}
@@ -0,0 +1,31 @@
+pub use num_rational::BigRational;
+use num_traits::ToPrimitive;
+
+use crate::{AsFloat, AsInteger, AsNumber, Float, Integer};
+
+impl AsNumber<i64> for BigRational {
+    fn as_number(&self) -> i64 {
+        match self.to_integer().to_i64() {
+            Some(value) if self.is_integer() => value,
+            _ => panic!("cannot convert from {} to {}", self, Integer::type_name()),
+        }
+    }
+}
+
+impl AsInteger for BigRational {
+    fn as_integer(&self) -> Integer {
+        Integer::from(AsNumber::<i64>::as_number(self))
+    }
+}
+
+impl AsNumber<f64> for BigRational {
+    fn as_number(&self) -> f64 {
+        self.to_f64().unwrap_or(f64::NAN)
+    }
+}
+
+impl AsFloat for BigRational {
+    fn as_float(&self) -> Float {
+        Float::from(AsNumber::<f64>::as_number(self))
+    }
+}
@@ -0,0 +1,106 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// a fixed length array of `T` indexable in constant time.
+///
+/// Clones share their elements, so that [`set`](Vector::set) through
+/// one clone is seen by all of them, while comparison and hashing
+/// look at the elements only.
+///
+/// A vector must therefore not be mutated while it is a key of a
+/// `HashMap`, a `HashSet` or an `equal`
+/// [`HashTable`](crate::HashTable), which would no longer find it.
+/// `eq` and `eql` hash tables hash vectors by identity and keep
+/// finding mutated keys.
+#[derive(Clone, Default)]
+pub struct Vector<T> {
+    items: Rc<RefCell<Vec<T>>>,
+}
+
+impl<T: Clone> Vector<T> {
+    pub fn new(items: Vec<T>) -> Vector<T> {
+        Vector {
+            items: Rc::new(RefCell::new(items)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.borrow().is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.items.borrow().get(index).cloned()
+    }
+
+    /// replaces the element at `index`, false when `index` is out of
+    /// range
+    pub fn set(&self, index: usize, item: T) -> bool {
+        match self.items.borrow_mut().get_mut(index) {
+            Some(slot) => {
+                *slot = item;
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.items.borrow().clone()
+    }
+
+    /// true when `self` and `other` are clones of the same vector
+    pub fn is_same(&self, other: &Vector<T>) -> bool {
+        Rc::ptr_eq(&self.items, &other.items)
+    }
+
+    /// an address that identifies the vector the way
+    /// [`is_same`](Vector::is_same) does, for hashing by identity
+    pub(crate) fn identity(&self) -> usize {
+        Rc::as_ptr(&self.items) as *const () as usize
+    }
+}
+
+impl<T: PartialEq> PartialEq for Vector<T> {
+    fn eq(&self, other: &Vector<T>) -> bool {
+        *self.items.borrow() == *other.items.borrow()
+    }
+}
+
+impl<T: Eq> Eq for Vector<T> {}
+
+impl<T: PartialOrd> PartialOrd for Vector<T> {
+    fn partial_cmp(&self, other: &Vector<T>) -> Option<Ordering> {
+        self.items.borrow().partial_cmp(&*other.items.borrow())
+    }
+}
+
+impl<T: Ord> Ord for Vector<T> {
+    fn cmp(&self, other: &Vector<T>) -> Ordering {
+        self.items.borrow().cmp(&*other.items.borrow())
+    }
+}
+
+impl<T: Hash> Hash for Vector<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.borrow().hash(state);
+    }
+}
+
+impl<T: Debug> Debug for Vector<T> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self.items.borrow())
+    }
+}
+
+impl<T: Clone> From<Vec<T>> for Vector<T> {
+    fn from(items: Vec<T>) -> Vector<T> {
+        Vector::new(items)
+    }
+}
@@ -1,7 +1,7 @@
 #![allow(unused)]
 use k9::assert_equal;
 use sexprs_data_structures::{
-    append, assert_debug_equal, assert_display_equal, car, cdr, cons, list, setcar,
+    append, assert_debug_equal, assert_display_equal, car, cdr, cons, list, pair, setcar,
     setcdr, AsValue, Cell, Value,
 };
 
@@ -82,9 +82,57 @@ fn test_append() {
 fn test_setcdr() {
     let mut cell = cons(Value::from(1), &mut Cell::from(Value::from(2)));
     // create ring
-    setcdr(cell.tail.inner_mut(), &cell);
+    setcdr(cell.tail_mut().unwrap(), &cell);
 
     assert_equal!(car(&cell.as_value()), Value::from(1));
     assert_equal!(car(&cdr(&cell.as_value())), Value::from(2));
     assert_equal!(car(&cdr(&cdr(&cell.as_value()))), Value::from(1));
 }
+
+#[test]
+fn test_identity_after_setcar_and_setcdr() {
+    let mut cell = cons(Value::from(1), &mut Cell::from(Value::from(2)));
+    let copy = cons(Value::from(1), &mut Cell::from(Value::from(2)));
+    assert_equal!(cell.as_value().is_eq(&cell.as_value()), true);
+    assert_equal!(cell.as_value().is_eq(&copy.as_value()), false);
+    assert_equal!(cell.as_value().is_equal(&copy.as_value()), true);
+
+    setcar(&mut cell, &Value::from(3));
+    assert_equal!(cell.as_value().is_equal(&copy.as_value()), false);
+
+    // create ring
+    setcdr(cell.tail_mut().unwrap(), &cell);
+    assert_equal!(cdr(&cdr(&cell.as_value())).is_eq(&cell.as_value()), true);
+    assert_equal!(cdr(&cell.as_value()).is_eq(&cell.as_value()), false);
+}
+
+#[test]
+fn test_pair() {
+    let value = pair(Value::symbol("a"), Value::symbol("b"));
+    assert_display_equal!(value, "(a . b)");
+    assert_equal!(car(&value), Value::symbol("a"));
+    assert_equal!(cdr(&value), Value::symbol("b"));
+    assert_equal!(value.len(), 1);
+
+    let value = pair(Value::symbol("a"), value);
+    assert_display_equal!(value, "(a a . b)");
+    assert_equal!(cdr(&cdr(&value)), Value::symbol("b"));
+
+    let value = pair(Value::symbol("a"), list([Value::symbol("b")]));
+    assert_display_equal!(value, "(a b)");
+    assert_equal!(value, list([Value::symbol("a"), Value::symbol("b")]));
+}
+
+#[test]
+fn test_setcdr_of_dotted_pair() {
+    let mut cell = cons(Value::from(1), &mut Cell::nil());
+    cell.set_cdr(Value::from(2));
+    assert_display_equal!(cell.as_value(), "(1 . 2)");
+    setcdr(&mut cell, &Cell::from(Value::from(3)));
+    assert_display_equal!(cell.as_value(), "(1 3)");
+
+    cell.set_cdr(Value::symbol("c"));
+    assert_display_equal!(cell.as_value(), "(1 . c)");
+    assert_equal!(cell.dotted(), Some(Value::symbol("c")));
+    assert_equal!(cell.is_proper(), false);
+}
@@ -0,0 +1,31 @@
+use k9::assert_equal;
+use sexprs_data_structures::{AsSymbol, Symbol, Value};
+
+#[test]
+fn symbols_of_the_same_name_share_their_storage() {
+    let symbol = Symbol::new("interned");
+    let other = Symbol::new(String::from("interned"));
+    assert_equal!(symbol, other);
+    assert_equal!(symbol.symbol().as_ptr(), other.symbol().as_ptr());
+    assert_equal!(symbol.quote().symbol().as_ptr(), symbol.symbol().as_ptr());
+    assert_equal!("interned".as_symbol(), symbol);
+}
+
+#[test]
+fn symbols_compare_by_name_regardless_of_quoting() {
+    assert_equal!(Symbol::new("a").quote(), Symbol::new("a"));
+    assert_equal!(Symbol::new("a") == Symbol::new("b"), false);
+    assert_equal!(Symbol::new("a") < Symbol::new("b"), true);
+    assert_equal!(Value::symbol("a").is_eq(&Value::quoted_symbol("a")), true);
+}
+
+#[test]
+fn unused_symbol_names_are_released() {
+    let kept = Symbol::new("kept");
+    for index in 0..10000 {
+        let symbol = Symbol::new(format!("k{}", index));
+        assert_equal!(symbol.symbol(), format!("k{}", index));
+    }
+    assert_equal!(Symbol::interned() < 4096, true);
+    assert_equal!(Symbol::new("kept").symbol().as_ptr(), kept.symbol().as_ptr());
+}
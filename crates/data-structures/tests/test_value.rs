@@ -1,4 +1,4 @@
-use sexprs_data_structures::Value;
+use sexprs_data_structures::{HashTable, HashTableTest, Value};
 use k9::assert_equal;
 
 #[test]
@@ -34,3 +34,104 @@ fn value_symbol() {
 fn value_integer() {
     assert_equal!(Value::integer(1), Value::integer(1));
 }
+
+#[test]
+fn value_string_is_freed_with_its_last_clone() {
+    let value = Value::string("owned");
+    let clone = value.clone();
+    let weak = match &value {
+        Value::String(string) => std::rc::Rc::downgrade(string),
+        _ => unreachable!(),
+    };
+    assert_equal!(value.is_eq(&clone), true);
+    assert_equal!(value.is_eq(&Value::string("owned")), false);
+    drop(value);
+    assert_equal!(weak.upgrade().is_some(), true);
+    drop(clone);
+    assert_equal!(weak.upgrade().is_none(), true);
+}
+
+#[test]
+fn value_strings_built_in_a_loop_are_freed() {
+    let mut value = Value::string("");
+    let mut weaks = Vec::new();
+    for index in 0..1000 {
+        if let Value::String(string) = &value {
+            weaks.push(std::rc::Rc::downgrade(string));
+            value = Value::string(format!("{}{}", string, index));
+        }
+    }
+    drop(value);
+    assert_equal!(weaks.iter().filter(|weak| weak.upgrade().is_some()).count(), 0);
+}
+
+#[test]
+// vectors hash by their elements, which holds as long as the vectors
+// in the set are not mutated, see `Vector`
+#[allow(clippy::mutable_key_type)]
+fn value_vectors_compare_by_contents_and_share_by_clone() {
+    let value = Value::vector([Value::integer(1), Value::from("a")]);
+    let clone = value.clone();
+    let copy = Value::vector([Value::integer(1), Value::from("a")]);
+    assert_equal!(value == copy, true);
+    assert_equal!(value.is_equal(&copy), true);
+    assert_equal!(value.is_eq(&copy), false);
+    assert_equal!(value.is_eq(&clone), true);
+    assert_equal!(Value::vector([Value::integer(1)]) < Value::vector([Value::integer(2)]), true);
+    assert_equal!(Value::bytes([1u8, 2]) == Value::bytes(vec![1u8, 2]), true);
+
+    let mut set = std::collections::HashSet::new();
+    set.insert(copy.clone());
+    assert_equal!(set.contains(&value), true);
+
+    if let Value::Vector(vector) = &clone {
+        vector.set(0, Value::integer(2));
+    }
+    assert_equal!(value.to_string(), r#"[2 "a"]"#);
+    assert_equal!(value == copy, false);
+}
+
+#[test]
+fn value_vectors_mutated_as_keys_are_found_by_identity_tables() {
+    let key = Value::vector([Value::integer(1)]);
+    let eq = HashTable::new(HashTableTest::Eq);
+    let eql = HashTable::new(HashTableTest::Eql);
+    eq.insert(key.clone(), Value::T);
+    eql.insert(key.clone(), Value::T);
+    if let Value::Vector(vector) = &key {
+        vector.set(0, Value::integer(2));
+    }
+    assert_equal!(eq.get(&key), Some(Value::T));
+    assert_equal!(eql.get(&key), Some(Value::T));
+}
+
+#[test]
+fn value_hash_tables_find_keys_by_their_test() {
+    let eq = HashTable::new(HashTableTest::Eq);
+    let equal = HashTable::new(HashTableTest::Equal);
+    let key = Value::from("key");
+    eq.insert(key.clone(), Value::integer(1));
+    equal.insert(key.clone(), Value::integer(1));
+    assert_equal!(eq.get(&key), Some(Value::integer(1)));
+    assert_equal!(eq.get(&Value::from("key")), None);
+    assert_equal!(equal.get(&Value::from("key")), Some(Value::integer(1)));
+
+    let eql = HashTable::new(HashTableTest::Eql);
+    eql.insert(Value::float(1.5), Value::T);
+    eql.insert(Value::symbol("a"), Value::T);
+    assert_equal!(eql.get(&Value::float(1.5)), Some(Value::T));
+    assert_equal!(eql.get(&Value::quoted_symbol("a")), Some(Value::T));
+    assert_equal!(eql.get(&Value::integer(1)), None);
+
+    equal.insert(Value::symbol("b"), Value::integer(2));
+    equal.insert(Value::from("key"), Value::integer(3));
+    assert_equal!(equal.len(), 2);
+    assert_equal!(equal.keys(), vec![key.clone(), Value::symbol("b")]);
+    assert_equal!(equal.remove(&Value::from("key")), true);
+    assert_equal!(equal.remove(&Value::from("key")), false);
+    assert_equal!(equal.get(&Value::symbol("b")), Some(Value::integer(2)));
+    assert_equal!(
+        Value::HashTable(equal).to_string(),
+        "#s(hash-table test equal data (b 2))"
+    );
+}
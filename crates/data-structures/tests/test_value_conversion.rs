@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 
 use k9::assert_equal;
-use sexprs_data_structures::{assert_display_equal, Value, Symbol};
+use sexprs_data_structures::{
+    assert_display_equal, AsFloat, AsInteger, BigInt, Float, Integer, Symbol, Value,
+};
 
 #[test]
 fn value_from_symbol() {
@@ -35,3 +37,16 @@ fn value_from_string() {
 fn value_display_nil() {
     assert_display_equal!(Value::Nil, "nil");
 }
+#[test]
+fn value_big_integer_and_ratio_conversion() {
+    let big: BigInt = BigInt::from(1i64) << 70usize;
+    assert_display_equal!(Value::big_integer(big.clone()), "1180591620717411303424");
+    assert_equal!(Value::from(big.clone()), Value::BigInt(big.clone()));
+    assert_equal!(Value::big_integer(42).as_integer(), Integer::from(42i64));
+    assert_equal!(Value::big_integer(big).as_float(), Float::from(1180591620717411303424.0));
+    assert_display_equal!(Value::ratio(6, -8), "-3/4");
+    assert_equal!(Value::ratio(3, 4).as_float(), Float::from(0.75));
+    assert_equal!(Value::ratio(8, 4).as_integer(), Integer::from(2i64));
+    assert_equal!(Value::integer(-3i64).as_float(), Float::from(-3.0));
+    assert_equal!(Value::unsigned_integer(3u32).as_integer(), Integer::from(3i64));
+}
@@ -15,6 +15,18 @@ fn test_string() {
     assert_display_equal!(Value::string("string"), r#""string""#);
 }
 #[test]
+fn test_string_escapes() {
+    assert_display_equal!(Value::string("say \"hi\"\n\\"), r#""say \"hi\"\n\\""#);
+}
+#[test]
+fn test_char() {
+    assert_display_equal!(Value::char('a'), "?a");
+    assert_display_equal!(Value::char(' '), "?\\s");
+    assert_display_equal!(Value::char('\n'), "?\\n");
+    assert_display_equal!(Value::char('('), "?\\(");
+    assert_display_equal!(Value::char('\u{7}'), "?\\u{7}");
+}
+#[test]
 fn test_symbol() {
     assert_display_equal!(Value::symbol("symbol"), "symbol");
 }
@@ -142,3 +154,10 @@ fn test_value_symbol_quoted_twice() {
     let value = value.quote();
     assert_display_equal!(&value, "'a");
 }
+
+#[test]
+fn test_value_vector_and_bytes() {
+    let value = Value::vector([Value::from("a"), Value::symbol("b"), Value::vector([])]);
+    assert_display_equal!(&value, r#"["a" b []]"#);
+    assert_display_equal!(Value::bytes([0u8, 255]), "#u8(0 255)");
+}
@@ -4,21 +4,88 @@ pub use errors::{Caller, Error, Result};
 pub mod macros;
 pub mod test;
 
+use std::rc::Rc;
 use std::str::FromStr;
 
-use sexprs_data_structures::{Cell, Value};
+use sexprs_data_structures::{BigInt, BigRational, Cell, HashTable, HashTableTest, Value};
 use sexprs_util::unexpected;
 use pest::iterators::{Pair, Pairs};
 use pest::Parser;
 use pest_derive::Parser;
 pub mod source;
-pub use source::{Source, Span, SpanPosition};
+pub use source::{Location, Locations, Source, Span, SpanPosition};
 pub const GRAMMAR: &'static str = include_str!("./grammar.pest");
 
 #[derive(Parser, Debug, Clone)]
 #[grammar = "src/grammar.pest"]
 pub struct MinilispSource;
-pub fn parse_source<'a>(input: &'a str) -> Result<'a, Value<'a>> {
+pub fn parse_source<'a, 'c>(input: &'a str) -> Result<'a, Value<'c>> {
+    Ok(parse_program(input)?.into_iter().next().unwrap_or_default())
+}
+
+pub fn parse_program<'a, 'c>(input: &'a str) -> Result<'a, Vec<Value<'c>>> {
+    read_program(input, None)
+}
+
+/// like [`parse_program`] but also returns the [`Location`] of every
+/// list read, attributed to `filename` when given
+pub fn parse_program_with_locations<'a, 'c>(
+    input: &'a str,
+    filename: Option<&str>,
+) -> Result<'a, (Vec<Value<'c>>, Locations<'c>)> {
+    let mut reader = Reader {
+        filename: filename.map(Rc::from),
+        locations: Locations::new(),
+    };
+    let forms = read_program(input, Some(&mut reader))?;
+    Ok((forms, reader.locations))
+}
+
+/// records the location of the lists read by [`read_value`]
+struct Reader<'c> {
+    filename: Option<Rc<str>>,
+    locations: Locations<'c>,
+}
+
+fn read_program<'a, 'c>(input: &'a str, mut reader: Option<&mut Reader<'c>>) -> Result<'a, Vec<Value<'c>>> {
+    let file = parse_file(input)?;
+    let mut forms = Vec::<Value<'c>>::new();
+    for statement in file.into_inner() {
+        if statement.as_rule() != Rule::statement {
+            continue;
+        }
+        for form in statement.into_inner() {
+            if form.as_rule() != Rule::COMMENT {
+                forms.push(read_value(form, reader.as_deref_mut()));
+            }
+        }
+    }
+    Ok(forms)
+}
+
+/// returns the top-level forms of `input` as [`Span`]s, including
+/// comments, in the order they appear in the source.
+pub fn parse_program_lossless<'a>(input: &'a str) -> Result<'a, Vec<Span<'a>>> {
+    let file = parse_file(input)?;
+    let source = Source {
+        source: Cow::from(input),
+        filename: None,
+    };
+    let mut spans = Vec::<Span<'a>>::new();
+    for pair in file.into_inner() {
+        match pair.as_rule() {
+            Rule::statement =>
+                for pair in pair.into_inner() {
+                    spans.push(Span::from_pair_lossless(pair, source.clone()));
+                },
+            Rule::COMMENT => spans.push(Span::from_pair_lossless(pair, source.clone())),
+            _ => continue,
+        }
+    }
+    Ok(spans)
+}
+
+fn parse_file<'a>(input: &'a str) -> Result<'a, Pair<'a, Rule>> {
     let source_info = Source {
         source: Cow::from(input),
         filename: None,
@@ -29,31 +96,31 @@ pub fn parse_source<'a>(input: &'a str) -> Result<'a, Value<'a>> {
             Some(Span::from_error(e, source_info.clone())),
         )
     })?;
-    let file = pairs.next().unwrap();
-    let nodes = (|pair: Pair<'a, Rule>| -> Option<Value<'a>> {
-        Some(pair_to_value(
-            pair.clone()
-                .into_inner()
-                .next()?
-                .into_inner()
-                .next()?,
-        ))
-    })(file)
-    .unwrap_or_default();
-    Ok(nodes)
-}
-
-pub fn map_pairs_to_list<'a>(pairs: Pairs<'a, Rule>) -> Value<'a> {
+    pairs
+        .next()
+        .ok_or_else(|| Error::new("expected a program", None))
+}
+
+pub fn map_pairs_to_list<'a, 'c>(pairs: Pairs<'a, Rule>) -> Value<'c> {
     pairs.map(|pair| pair_to_value(pair)).collect()
 }
-pub fn pair_to_value<'a>(pair: Pair<'a, Rule>) -> Value<'a> {
+pub fn pair_to_value<'a, 'c>(pair: Pair<'a, Rule>) -> Value<'c> {
+    read_value(pair, None)
+}
+
+/// reads `pair` into a value, recording the location of each list
+/// in `reader` when given
+fn read_value<'a, 'c>(pair: Pair<'a, Rule>, mut reader: Option<&mut Reader<'c>>) -> Value<'c> {
     match pair.as_rule() {
         Rule::float =>
             Value::float(f64::from_str(pair.as_span().as_str()).expect("float")),
-        Rule::integer =>
-            Value::integer(i64::from_str(pair.as_span().as_str()).expect("integer")),
-        Rule::string => Value::string(Cow::from(pair.as_span().as_str())),
-        Rule::symbol => Value::symbol(Cow::from(pair.as_span().as_str())),
+        Rule::integer => integer_to_value(pair.as_span().as_str()),
+        Rule::ratio => ratio_to_value(pair.as_span().as_str()),
+        Rule::string => Value::string(unescape(pair.as_span().as_str())),
+        Rule::char => Value::Char(char_to_value(pair.as_span().as_str())),
+        Rule::double_quoted_string =>
+            read_value(pair.clone().into_inner().next().expect("string"), reader),
+        Rule::symbol => Value::symbol(pair.as_span().as_str()),
         Rule::quoted_symbol => {
             let mut pairs = pair.clone().into_inner();
             pairs.next().expect("quote");
@@ -61,12 +128,16 @@ pub fn pair_to_value<'a>(pair: Pair<'a, Rule>) -> Value<'a> {
             Value::quoted_symbol(symbol.as_span().as_str())
         },
         Rule::t => Value::T,
-        Rule::unsigned => Value::unsigned_integer(
-            u32::from_str(pair.as_span().as_str()).expect("unsigned integer"),
-        ),
-        Rule::value => pair_to_value(pair.clone().into_inner().next().expect("value")),
+        Rule::unsigned => match u32::from_str(pair.as_span().as_str()) {
+            Ok(value) => Value::unsigned_integer(value),
+            Err(_) => integer_to_value(pair.as_span().as_str()),
+        },
+        Rule::value => read_value(pair.clone().into_inner().next().expect("value"), reader),
         Rule::sexpr => {
+            let span = pair.as_span();
+            let mut positions = Vec::<(SpanPosition, SpanPosition)>::new();
             let mut items = Cell::nil();
+            let mut dotted = None;
             let mut pairs = pair.clone().into_inner();
             let mut quoted = false;
             loop {
@@ -82,21 +153,186 @@ pub fn pair_to_value<'a>(pair: Pair<'a, Rule>) -> Value<'a> {
                     },
                     Rule::open_paren => continue,
                     Rule::close_paren => continue,
+                    Rule::COMMENT => continue,
+                    Rule::dotted => {
+                        let cdr = pair
+                            .into_inner()
+                            .filter(|pair| pair.as_rule() != Rule::COMMENT)
+                            .nth(1)
+                            .expect("dotted cdr");
+                        dotted = Some(read_value(cdr, reader.as_deref_mut()));
+                    },
                     _ => {
-                        items.push_value(pair_to_value(pair));
+                        positions.push((
+                            SpanPosition::from_pest(pair.as_span().start_pos()),
+                            SpanPosition::from_pest(pair.as_span().end_pos()),
+                        ));
+                        items.push_value(read_value(pair, reader.as_deref_mut()));
                         continue;
                     },
                 }
             }
             pairs.next().expect("close_paren");
-            let value = Value::from_iter(items.into_iter());
-            if quoted {
-                value.quote()
-            } else {
-                value
+            let value = match dotted {
+                Some(cdr) => {
+                    items.set_last_cdr(cdr);
+                    Value::list(items)
+                },
+                None => Value::from_iter(items.into_iter()),
+            };
+            let value = if quoted { value.quote() } else { value };
+            if let Some(reader) = reader {
+                let mut location = Location::new(
+                    reader.filename.clone(),
+                    SpanPosition::from_pest(span.start_pos()),
+                    SpanPosition::from_pest(span.end_pos()),
+                );
+                location.items = positions;
+                reader.locations.insert(&value, location);
             }
+            value
         },
+        Rule::vector => Value::vector(
+            pair.into_inner()
+                .filter(|pair| {
+                    !matches!(
+                        pair.as_rule(),
+                        Rule::open_bracket | Rule::close_bracket | Rule::close_paren | Rule::COMMENT
+                    )
+                })
+                .map(|pair| read_value(pair, reader.as_deref_mut())),
+        ),
+        Rule::bytes => Value::bytes(
+            pair.into_inner()
+                .filter(|pair| pair.as_rule() == Rule::byte)
+                .map(|pair| u8::from_str(pair.as_span().as_str()).expect("byte"))
+                .collect::<Vec<u8>>(),
+        ),
+        Rule::hash_table => hash_table_to_value(pair, reader),
+        Rule::backquoted => quasiquote_to_value("backquote", pair, reader),
+        Rule::unquoted => quasiquote_to_value("unquote", pair, reader),
+        Rule::unquoted_splicing => quasiquote_to_value("unquote-splicing", pair, reader),
         Rule::nil => Value::nil(),
         _ => unexpected!(pair),
     }
 }
+
+/// reads `#s(hash-table test equal data (key value ...))`, where
+/// both `test` and `data` are optional. The grammar only accepts
+/// data with an even number of elements.
+fn hash_table_to_value<'a, 'c>(pair: Pair<'a, Rule>, mut reader: Option<&mut Reader<'c>>) -> Value<'c> {
+    let mut test = HashTableTest::default();
+    let mut entries = Vec::<Value<'c>>::new();
+    for pair in pair.into_inner() {
+        match pair.as_rule() {
+            Rule::hash_table_test => {
+                let name = pair.into_inner().find(|pair| pair.as_rule() == Rule::test_name).expect("test name");
+                test = HashTableTest::from_name(name.as_span().as_str()).expect("test name");
+            },
+            Rule::hash_table_data => {
+                entries = pair
+                    .into_inner()
+                    .filter(|pair| {
+                        !matches!(
+                            pair.as_rule(),
+                            Rule::data_keyword | Rule::open_paren | Rule::close_paren | Rule::COMMENT
+                        )
+                    })
+                    .map(|pair| read_value(pair, reader.as_deref_mut()))
+                    .collect();
+            },
+            _ => {},
+        }
+    }
+    assert_eq!(entries.len() % 2, 0, "hash table data with a value for each key");
+    let table = HashTable::new(test);
+    let mut entries = entries.into_iter();
+    while let (Some(key), Some(value)) = (entries.next(), entries.next()) {
+        table.insert(key, value);
+    }
+    Value::HashTable(table)
+}
+
+/// reads integers that do not fit `i64` as [`BigInt`]s
+fn integer_to_value<'c>(input: &str) -> Value<'c> {
+    match i64::from_str(input) {
+        Ok(value) => Value::integer(value),
+        Err(_) => Value::big_integer(BigInt::from_str(input).expect("integer")),
+    }
+}
+
+/// reads `numerator/denominator` as a [`BigRational`] in lowest
+/// terms, or as an integer when the denominator divides the numerator
+fn ratio_to_value<'c>(input: &str) -> Value<'c> {
+    let (numerator, denominator) = input.split_once('/').expect("ratio");
+    let ratio = BigRational::new(
+        BigInt::from_str(numerator).expect("numerator"),
+        BigInt::from_str(denominator).expect("denominator"),
+    );
+    if ratio.is_integer() {
+        integer_to_value(&ratio.to_integer().to_string())
+    } else {
+        Value::Ratio(ratio)
+    }
+}
+
+/// replaces the escape sequences of a string literal by the
+/// characters they stand for: `\n`, `\t`, `\r`, `\s` (space), `\0`,
+/// `\u{XXXX}` and any other character after a backslash as itself.
+/// Escaped code points that are not characters read as U+FFFD
+pub fn unescape(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            result.push(char);
+            continue;
+        }
+        result.push(match chars.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('s') => ' ',
+            Some('0') => '\0',
+            Some('u') if chars.as_str().starts_with('{') => {
+                let (code, rest) = chars.as_str()[1..].split_once('}').expect("unicode escape");
+                let char = u32::from_str_radix(code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .unwrap_or(char::REPLACEMENT_CHARACTER);
+                chars = rest.chars();
+                char
+            },
+            Some(char) => char,
+            None => '\\',
+        });
+    }
+    result
+}
+
+/// reads `?c`, `?\n` or `#\c` and the names `#\space`,
+/// `#\newline`, `#\tab` and `#\return`
+fn char_to_value(input: &str) -> char {
+    let literal = match input.strip_prefix('?') {
+        Some(literal) => literal,
+        None => match &input[2..] {
+            "space" => return ' ',
+            "newline" => return '\n',
+            "tab" => return '\t',
+            "return" => return '\r',
+            literal => literal,
+        },
+    };
+    unescape(literal).chars().next().expect("char")
+}
+
+/// reads `` `form ``, `,form` and `,@form` into `(backquote form)`,
+/// `(unquote form)` and `(unquote-splicing form)` respectively
+fn quasiquote_to_value<'a, 'c>(name: &str, pair: Pair<'a, Rule>, reader: Option<&mut Reader<'c>>) -> Value<'c> {
+    let form = pair
+        .into_inner()
+        .filter(|pair| pair.as_rule() != Rule::COMMENT)
+        .nth(1)
+        .expect("quasiquoted form");
+    Value::from_iter([Value::symbol(name), read_value(form, reader)])
+}
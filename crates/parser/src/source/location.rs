@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+use sexprs_data_structures::{Cell, Value, WeakCell};
+
+use crate::SpanPosition;
+
+/// where a list was read: the file, when known, the positions of the
+/// list and the positions of each of its items
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Location {
+    pub filename: Option<Rc<str>>,
+    pub start_pos: SpanPosition,
+    pub end_pos: SpanPosition,
+    pub items: Vec<(SpanPosition, SpanPosition)>,
+}
+
+impl Location {
+    pub fn new(
+        filename: Option<Rc<str>>,
+        start_pos: SpanPosition,
+        end_pos: SpanPosition,
+    ) -> Location {
+        Location {
+            filename,
+            start_pos,
+            end_pos,
+            items: Vec::new(),
+        }
+    }
+
+    /// the location of the item at `index` of the list
+    pub fn item(&self, index: usize) -> Option<Location> {
+        let (start_pos, end_pos) = self.items.get(index)?.clone();
+        Some(Location::new(self.filename.clone(), start_pos, end_pos))
+    }
+
+    pub fn start_pos(&self) -> (usize, usize) {
+        self.start_pos.to_tuple()
+    }
+
+    pub fn end_pos(&self) -> (usize, usize) {
+        self.end_pos.to_tuple()
+    }
+}
+
+/// `file:line:column`, or `line L, column C` when the source was not
+/// read from a file
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let (line, column) = self.start_pos();
+        match &self.filename {
+            Some(filename) => write!(f, "{}:{}:{}", filename, line, column),
+            None => write!(f, "line {}, column {}", line, column),
+        }
+    }
+}
+
+/// the locations of the lists read by
+/// [`parse_program_with_locations`](crate::parse_program_with_locations),
+/// keyed by the identity of their cells so that the lists keep their
+/// location when bound, stored in a function body or evaluated.
+///
+/// Each location holds a weak reference to its cell, so that the
+/// identity is not reused by another cell while the location is
+/// kept, and [`extend`](Locations::extend) forgets the locations of
+/// the cells that were freed.
+#[derive(Clone, Debug, Default)]
+pub struct Locations<'c> {
+    lists: HashMap<usize, (WeakCell<'c>, Location)>,
+}
+
+impl<'c> Locations<'c> {
+    pub fn new() -> Locations<'c> {
+        Locations::default()
+    }
+
+    pub fn insert(&mut self, list: &Value<'c>, location: Location) {
+        if let Some(cell) = cell(list) {
+            self.lists.insert(cell.identity(), (cell.downgrade(), location));
+        }
+    }
+
+    pub fn get(&self, list: &Value<'c>) -> Option<&Location> {
+        let (_, location) = self.lists.get(&cell(list)?.identity())?;
+        Some(location)
+    }
+
+    pub fn contains(&self, list: &Value<'c>) -> bool {
+        cell(list).is_some_and(|cell| self.lists.contains_key(&cell.identity()))
+    }
+
+    pub fn extend(&mut self, locations: Locations<'c>) {
+        self.lists.retain(|_, (cell, _)| !cell.is_dropped());
+        self.lists.extend(locations.lists);
+    }
+
+    pub fn len(&self) -> usize {
+        self.lists.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lists.is_empty()
+    }
+}
+
+fn cell<'a, 'c>(list: &'a Value<'c>) -> Option<&'a Cell<'c>> {
+    match list {
+        Value::List(cell) | Value::QuotedList(cell) if !cell.is_nil() => Some(cell),
+        _ => None,
+    }
+}
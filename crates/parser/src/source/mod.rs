@@ -1,6 +1,8 @@
 pub mod info;
 pub mod span;
 pub mod position;
+pub mod location;
 pub use info::Source;
 pub use span::Span;
 pub use position::SpanPosition;
+pub use location::{Location, Locations};
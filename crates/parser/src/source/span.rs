@@ -17,6 +17,21 @@ pub struct Span<'a> {
 }
 impl<'a> Span<'a> {
     pub fn from_pair(pair: Pair<'a, Rule>, source: Source<'a>) -> Span<'a> {
+        Span::from_pair_with_comments(pair, source, false)
+    }
+
+    /// like [`Span::from_pair`] but keeps `COMMENT` spans in
+    /// [`Span::inner`] for tools that need a lossless view of the
+    /// source.
+    pub fn from_pair_lossless(pair: Pair<'a, Rule>, source: Source<'a>) -> Span<'a> {
+        Span::from_pair_with_comments(pair, source, true)
+    }
+
+    fn from_pair_with_comments(
+        pair: Pair<'a, Rule>,
+        source: Source<'a>,
+        comments: bool,
+    ) -> Span<'a> {
         let span = pair.as_span();
         let start_pos = SpanPosition::from_pest(span.start_pos());
         let end_pos = SpanPosition::from_pest(span.end_pos());
@@ -28,20 +43,22 @@ impl<'a> Span<'a> {
             end_pos,
             source: source.clone(),
             inner: {
-                let inner = pair.clone().into_inner();
-                if inner.peek().is_none() {
+                let inner = pair
+                    .clone()
+                    .into_inner()
+                    .filter(|pair| comments || pair.as_rule() != Rule::COMMENT)
+                    .map(|pair| {
+                        Span::from_pair_with_comments(
+                            pair.clone(),
+                            source.clone(),
+                            comments,
+                        )
+                    })
+                    .collect::<Vec<Span<'a>>>();
+                if inner.is_empty() {
                     None
                 } else {
-                    Some(
-                        inner
-                            .map(|pair| {
-                                Span::from_pair(
-                                    pair.clone(),
-                                    source.clone(),
-                                )
-                            })
-                            .collect(),
-                    )
+                    Some(inner)
                 }
             },
         }
@@ -66,6 +83,10 @@ impl<'a> Span<'a> {
         }
     }
 
+    pub fn is_comment(&self) -> bool {
+        self.name.as_deref() == Some("COMMENT")
+    }
+
     pub fn input(&'a self) -> &'a str {
         self.input.borrow()
     }
@@ -1,8 +1,10 @@
 #![allow(unused)]
+use std::str::FromStr;
+
 use k9::assert_equal;
-use sexprs_data_structures::{list, Value};
+use sexprs_data_structures::{list, pair, BigInt, HashTableTest, Value};
 use sexprs_parser::test::stub_input;
-use sexprs_parser::{parse_source, Result};
+use sexprs_parser::{parse_program, parse_program_lossless, parse_program_with_locations, parse_source, Result};
 use sexprs_util::vec_deque;
 
 #[test]
@@ -126,3 +128,324 @@ fn test_defun() -> Result<'static, ()> {
     );
     Ok(())
 }
+
+#[test]
+fn test_parse_program_returns_every_top_level_form() -> Result<'static, ()> {
+    let forms = parse_program(
+        r#"
+(defun sum (a b) (+ a b))
+(setq x 1) (setq y 2)
+
+(sum x y)
+"#,
+    )?;
+    assert_equal!(
+        forms,
+        vec![
+            list([
+                Value::symbol("defun"),
+                Value::symbol("sum"),
+                list([Value::symbol("a"), Value::symbol("b")]),
+                list([
+                    Value::symbol("+"),
+                    Value::symbol("a"),
+                    Value::symbol("b"),
+                ]),
+            ]),
+            list([
+                Value::symbol("setq"),
+                Value::symbol("x"),
+                Value::unsigned_integer(1u32),
+            ]),
+            list([
+                Value::symbol("setq"),
+                Value::symbol("y"),
+                Value::unsigned_integer(2u32),
+            ]),
+            list([Value::symbol("sum"), Value::symbol("x"), Value::symbol("y")]),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_parse_program_of_empty_input() -> Result<'static, ()> {
+    assert_equal!(parse_program("")?, Vec::<Value>::new());
+    assert_equal!(parse_program("\n  \n")?, Vec::<Value>::new());
+    Ok(())
+}
+
+#[test]
+fn test_line_comments_are_skipped() -> Result<'static, ()> {
+    let forms = parse_program(
+        r#"
+;; adds two numbers
+(+ 1 ; first
+   2) ; second
+"#,
+    )?;
+    assert_equal!(
+        forms,
+        vec![list([
+            Value::symbol("+"),
+            Value::unsigned_integer(1u32),
+            Value::unsigned_integer(2u32),
+        ])]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_nested_block_comments_are_skipped() -> Result<'static, ()> {
+    let items = parse_source(r#"#| outer #| inner |# still outer |# (list "a" #| b |# "c")"#)?;
+    assert_equal!(
+        items,
+        list([Value::symbol("list"), Value::from("a"), Value::from("c")])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_datum_comments_skip_the_next_form() -> Result<'static, ()> {
+    let forms = parse_program(r#"#;(ignored form) (list 'a #; 'b #;(c d) 'e)"#)?;
+    assert_equal!(
+        forms,
+        vec![list([
+            Value::symbol("list"),
+            Value::quoted_symbol("a"),
+            Value::quoted_symbol("e"),
+        ])]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_semicolon_inside_string_is_not_a_comment() -> Result<'static, ()> {
+    let items = parse_source(r#"(print "a ; b")"#)?;
+    assert_equal!(items, list([Value::symbol("print"), Value::from("a ; b")]));
+    Ok(())
+}
+
+#[test]
+fn test_lossless_parse_keeps_comments() -> Result<'static, ()> {
+    let spans = parse_program_lossless(";; header\n(a ; inline\n b) #| trailer |#")?;
+    assert_equal!(
+        spans
+            .iter()
+            .map(|span| (span.is_comment(), span.input.to_string()))
+            .collect::<Vec<(bool, String)>>(),
+        vec![
+            (true, ";; header".to_string()),
+            (false, "(a ; inline\n b)".to_string()),
+            (true, "#| trailer |#".to_string()),
+        ]
+    );
+    assert_equal!(spans[1].inner().iter().any(|span| span.is_comment()), true);
+    Ok(())
+}
+
+#[test]
+fn test_quasiquote_reads_into_backquote_unquote_forms() -> Result<'static, ()> {
+    assert_equal!(
+        parse_source("`(a ,b ,@rest)")?,
+        list([
+            Value::symbol("backquote"),
+            list([
+                Value::symbol("a"),
+                list([Value::symbol("unquote"), Value::symbol("b")]),
+                list([Value::symbol("unquote-splicing"), Value::symbol("rest")]),
+            ]),
+        ])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_nested_quasiquote() -> Result<'static, ()> {
+    assert_equal!(
+        parse_source("`(a `(b ,,c))")?,
+        list([
+            Value::symbol("backquote"),
+            list([
+                Value::symbol("a"),
+                list([
+                    Value::symbol("backquote"),
+                    list([
+                        Value::symbol("b"),
+                        list([
+                            Value::symbol("unquote"),
+                            list([Value::symbol("unquote"), Value::symbol("c")]),
+                        ]),
+                    ]),
+                ]),
+            ]),
+        ])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_symbols_may_contain_digits_and_start_like_t_or_nil() -> Result<'static, ()> {
+    assert_equal!(
+        parse_source("(macroexpand-1 tail nilp t nil)")?,
+        list([
+            Value::symbol("macroexpand-1"),
+            Value::symbol("tail"),
+            Value::symbol("nilp"),
+            Value::T,
+            Value::Nil,
+        ])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_parse_program_accepts_top_level_atoms() -> Result<'static, ()> {
+    assert_equal!(
+        parse_program("(setq x 1)\nx")?,
+        vec![
+            list([Value::symbol("setq"), Value::symbol("x"), Value::unsigned_integer(1u32)]),
+            Value::symbol("x"),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_parse_big_integers_and_ratios() -> Result<'static, ()> {
+    assert_equal!(
+        parse_source("(+ 123456789012345678901234567890 -3/4 6/3 4294967296 / x)")?,
+        list([
+            Value::symbol("+"),
+            Value::big_integer(BigInt::from_str("123456789012345678901234567890").unwrap()),
+            Value::ratio(-3, 4),
+            Value::integer(2i64),
+            Value::integer(4294967296i64),
+            Value::symbol("/"),
+            Value::symbol("x"),
+        ])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_parse_dotted_pairs() -> Result<'static, ()> {
+    assert_equal!(
+        parse_source("(a . b)")?,
+        pair(Value::symbol("a"), Value::symbol("b"))
+    );
+    assert_equal!(
+        parse_source("(a b . 3)")?,
+        pair(Value::symbol("a"), pair(Value::symbol("b"), Value::unsigned_integer(3u32)))
+    );
+    assert_equal!(parse_source("(a . (b c))")?, list([Value::symbol("a"), Value::symbol("b"), Value::symbol("c")]));
+    assert_equal!(parse_source("(a .b)")?, list([Value::symbol("a"), Value::symbol(".b")]));
+    assert_equal!(parse_source("'(a b . c)")?.to_string(), "'(a b . c)");
+    Ok(())
+}
+
+#[test]
+fn test_parse_string_escapes() -> Result<'static, ()> {
+    assert_equal!(
+        parse_source(r#""say \"hi\"\n\tand \\ \u{1F600}\s""#)?,
+        Value::from("say \"hi\"\n\tand \\ \u{1F600} ")
+    );
+    assert_equal!(parse_source(r#""\q\u{110000}""#)?, Value::from("q\u{FFFD}"));
+    Ok(())
+}
+
+#[test]
+fn test_parse_characters() -> Result<'static, ()> {
+    assert_equal!(
+        parse_source(r#"(?a #\b ?\n #\space ?\( ?\u{e9} #\))"#)?,
+        list([
+            Value::char('a'),
+            Value::char('b'),
+            Value::char('\n'),
+            Value::char(' '),
+            Value::char('('),
+            Value::char('é'),
+            Value::char(')'),
+        ])
+    );
+    assert_equal!(parse_source("(?ab b?)")?, list([Value::symbol("?ab"), Value::symbol("b?")]));
+    Ok(())
+}
+
+#[test]
+fn test_parse_vectors_and_bytes() -> Result<'static, ()> {
+    assert_equal!(
+        parse_source("[1 (a b) \"c\"]")?,
+        Value::vector([
+            Value::unsigned_integer(1u32),
+            list([Value::symbol("a"), Value::symbol("b")]),
+            Value::from("c"),
+        ])
+    );
+    assert_equal!(parse_source("#(a [])")?, Value::vector([Value::symbol("a"), Value::vector([])]));
+    assert_equal!(parse_source("#u8(0 7 42 255)")?, Value::bytes([0u8, 7, 42, 255]));
+    assert_equal!(parse_source("(a[b])")?, list([Value::symbol("a"), Value::vector([Value::symbol("b")])]));
+    assert_equal!(parse_source("#u8()")?.to_string(), "#u8()");
+    assert!(parse_source("#u8(256)").is_err());
+    assert!(parse_source("#u8(a)").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_hash_tables() -> Result<'static, ()> {
+    let value = parse_source(r#"#s(hash-table test equal data ("a" 1 (b c) [2]))"#)?;
+    let table = match &value {
+        Value::HashTable(table) => table.clone(),
+        value => panic!("not a hash table: {}", value),
+    };
+    assert_equal!(table.test(), HashTableTest::Equal);
+    assert_equal!(table.get(&Value::from("a")), Some(Value::unsigned_integer(1u32)));
+    assert_equal!(
+        table.get(&list([Value::symbol("b"), Value::symbol("c")])),
+        Some(Value::vector([Value::unsigned_integer(2u32)]))
+    );
+    assert_equal!(value.to_string(), r#"#s(hash-table test equal data ("a" 1 (b c) [2]))"#);
+    let source = value.to_string();
+    assert_equal!(parse_source(&source).map(|value| value.to_string()).ok(), Some(source.clone()));
+
+    assert_equal!(parse_source("#s(hash-table)")?.to_string(), "#s(hash-table test eql data ())");
+    assert_equal!(parse_source("#s(hash-table data (a 1 a 2))")?.to_string(), "#s(hash-table test eql data (a 2))");
+    assert!(parse_source("#s(hash-table test foo)").is_err());
+    assert!(parse_source("#s(hash-table data (a))").is_err());
+    assert!(parse_source("#s(hash-table data (a 1 b))").is_err());
+    assert!(parse_source("(list #s(hash-table test equal data (a 1 b)))").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_program_with_locations() -> Result<'static, ()> {
+    let (forms, locations) = parse_program_with_locations(
+        "(defun f (x)\n  ; comment\n  (g x 3.0))\n'(a b)",
+        Some("f.lisp"),
+    )?;
+    let body = forms[0].values()[3].clone();
+    let location = locations.get(&body).cloned().expect("body location");
+    assert_equal!(location.start_pos(), (3, 3));
+    assert_equal!(location.end_pos(), (3, 12));
+    assert_equal!(location.item(2).map(|item| item.start_pos()), Some((3, 8)));
+    assert_equal!(location.to_string(), "f.lisp:3:3");
+    assert_equal!(locations.get(&forms[0]).map(|location| location.to_string()), Some("f.lisp:1:1".to_string()));
+    assert_equal!(locations.get(&body.clone()).is_some(), true);
+    assert_equal!(locations.get(&list([Value::symbol("g"), Value::symbol("x")])).is_none(), true);
+    Ok(())
+}
+
+#[test]
+fn test_locations_of_freed_lists_are_forgotten() -> Result<'static, ()> {
+    let (forms, mut locations) = parse_program_with_locations("(a (b c))\n(d)", None)?;
+    assert_equal!(locations.len(), 3);
+    let kept = forms[0].values()[1].clone();
+    drop(forms);
+
+    let (others, other_locations) = parse_program_with_locations("(e f)", None)?;
+    locations.extend(other_locations);
+    assert_equal!(locations.len(), 2);
+    assert_equal!(locations.get(&kept).map(|location| location.start_pos()), Some((1, 4)));
+    assert_equal!(locations.get(&others[0]).map(|location| location.start_pos()), Some((1, 1)));
+    Ok(())
+}
@@ -5,7 +5,6 @@ use rustyline::{
     Changeset, CompletionType, Config, Context, DefaultEditor, Editor, Helper,
 };
 use sexprs_formatter::highlight;
-use sexprs_parser::parse_source;
 use sexprs_repl::{Result, VirtualMachinePrompt};
 use sexprs_util::color;
 use sexprs_vm::VirtualMachine;
@@ -20,6 +19,12 @@ fn print_error<T: std::fmt::Display>(error: T) {
         .join(" ")
     );
 }
+fn print_backtrace(vm: &VirtualMachine) {
+    let backtrace = vm.backtrace();
+    if !backtrace.is_empty() {
+        eprintln!("{}", color::ansi(backtrace.to_string(), 245, 16));
+    }
+}
 fn main() -> Result<()> {
     Ok(repl()?)
 }
@@ -39,7 +44,7 @@ fn header() {
 \x1b[1;38;5;220m
 \x1b[1;38;5;83mS\x1b[1;38;5;231m-\x1b[1;38;5;206mexprs \x1b[1;38;5;231mVM\x1b[1;38;5;83m version {}\x1b[0m", env!("CARGO_PKG_VERSION"));
 }
-fn repl<'a>() -> Result<()> {
+fn repl() -> Result<()> {
     let config = Config::builder()
         // .history_ignore_dups(true)?
         // .history_ignore_space(false)
@@ -65,20 +70,14 @@ fn repl<'a>() -> Result<()> {
         let readline = rl.readline(": ");
         match readline {
             Ok(line) => {
-                let line: &'a str = line.clone().leak();
-                rl.add_history_entry(line)?;
-                match parse_source(line) {
-                    Ok(value) => match vm.eval(value) {
-                        Ok(value) => {
-                            println!("{}", highlight(value.to_string(), "lisp")?);
-                        },
-                        Err(error) => {
-                            print_error(error);
-                        },
+                rl.add_history_entry(line.as_str())?;
+                match vm.eval_program(&line) {
+                    Ok(value) => {
+                        println!("{}", highlight(value.to_string(), "lisp")?);
                     },
                     Err(error) => {
                         print_error(error);
-                        continue;
+                        print_backtrace(&vm);
                     },
                 }
             },
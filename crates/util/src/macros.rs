@@ -185,12 +185,16 @@ macro_rules! unwrap_result {
 #[macro_export]
 macro_rules! impl_error {
     ($name:ident, $type:ty) => {
+        $crate::impl_error!($name, $type, {});
+    };
+    ($name:ident, $type:ty, { $( $field:ident: $field_type:ty ),* $(,)? }) => {
         #[derive(Clone, PartialEq, Eq)]
         pub struct Error {
             message: String,
             ty: $type,
             callers: Vec<sexprs_util::Caller>,
             previous: Option<Box<Error>>,
+            $( $field: $field_type, )*
         }
         impl Error {
             pub fn new<T: std::fmt::Display>(message: T, ty: $type) -> Self {
@@ -208,8 +212,13 @@ macro_rules! impl_error {
                     ty,
                     callers: Vec::new(),
                     previous: previous.map(Box::new),
+                    $( $field: Default::default(), )*
                 }
             }
+
+            pub fn ty(&self) -> $type {
+                self.ty.clone()
+            }
         }
         impl std::error::Error for $name {}
 
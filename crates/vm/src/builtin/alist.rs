@@ -0,0 +1,100 @@
+use sexprs_data_structures as ds;
+use sexprs_data_structures::Value;
+use sexprs_util::try_result;
+
+use super::arguments;
+use crate::{Context, Pointer, Result};
+
+/// `(assoc key alist &optional testfn)` returns the first element of
+/// `alist` whose car is `equal` to `key`, or passes `testfn`
+pub fn assoc<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "assoc", list, 2, Some(3)));
+    let test = Test::new(arguments.get(2), Value::is_equal);
+    Ok(try_result!(find(vm, &arguments[1], &arguments[0], &test, ds::car)).unwrap_or_default())
+}
+
+/// `(assq key alist)` returns the first element of `alist` whose car
+/// is `eq` to `key`
+pub fn assq<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "assq", list, 2, Some(2)));
+    let test = Test::new(None, Value::is_eq);
+    Ok(try_result!(find(vm, &arguments[1], &arguments[0], &test, ds::car)).unwrap_or_default())
+}
+
+/// `(rassoc value alist)` returns the first element of `alist` whose
+/// cdr is `equal` to `value`
+pub fn rassoc<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "rassoc", list, 2, Some(2)));
+    let test = Test::new(None, Value::is_equal);
+    Ok(try_result!(find(vm, &arguments[1], &arguments[0], &test, ds::cdr)).unwrap_or_default())
+}
+
+/// `(alist-get key alist &optional default remove testfn)` returns
+/// the cdr of the first element of `alist` whose car is `eq` to `key`,
+/// or passes `testfn`, and `default` when there is none. `remove` only
+/// matters to `setf` and is ignored.
+pub fn alist_get<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "alist-get", list, 2, Some(5)));
+    let test = Test::new(arguments.get(4), Value::is_eq);
+    Ok(
+        match try_result!(find(vm, &arguments[1], &arguments[0], &test, ds::car)) {
+            Some(element) => ds::cdr(&element),
+            None => arguments.get(2).cloned().unwrap_or_default(),
+        },
+    )
+}
+
+/// how elements are compared with a key: a lisp function given by
+/// the caller, or `default`
+pub(crate) struct Test<'a, 'c> {
+    function: Option<&'a Value<'c>>,
+    default: fn(&Value<'c>, &Value<'c>) -> bool,
+}
+
+impl<'a, 'c> Test<'a, 'c> {
+    /// a test calling `function` unless it is absent or nil
+    pub(crate) fn new(
+        function: Option<&'a Value<'c>>,
+        default: fn(&Value<'c>, &Value<'c>) -> bool,
+    ) -> Test<'a, 'c> {
+        Test {
+            function: function.filter(|function| !function.is_empty()),
+            default,
+        }
+    }
+
+    pub(crate) fn matches(
+        &self,
+        mut vm: Pointer<Context<'c>>,
+        item: &Value<'c>,
+        key: &Value<'c>,
+    ) -> Result<bool> {
+        match self.function {
+            Some(function) => Ok(!try_result!(vm
+                .inner_mut()
+                .funcall(function.clone(), Value::from_iter([item.clone(), key.clone()])))
+            .is_empty()),
+            None => Ok((self.default)(item, key)),
+        }
+    }
+}
+
+/// the first cons of `alist` whose `part` matches `key`, skipping
+/// elements which are not conses
+fn find<'c>(
+    vm: Pointer<Context<'c>>,
+    alist: &Value<'c>,
+    key: &Value<'c>,
+    test: &Test<'_, 'c>,
+    part: fn(&Value<'c>) -> Value<'c>,
+) -> Result<Option<Value<'c>>> {
+    for element in alist.values() {
+        if !element.is_list() || element.is_empty() {
+            continue;
+        }
+        if try_result!(test.matches(vm.clone(), &part(&element), key)) {
+            return Ok(Some(element));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,208 @@
+use sexprs_data_structures::{car, cdr, AsSymbol, Symbol, Value};
+use sexprs_util::try_result;
+
+use crate::helpers::runtime_error;
+use crate::{Context, Environment, Pointer, Result, Sym};
+
+/// `(let ((name value) ...) body...)` evaluates every value in the
+/// enclosing scope before binding any of them
+pub fn r#let<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let mut bindings = Vec::<(Symbol<'c>, Value<'c>)>::new();
+    for (pattern, form) in try_result!(binding_forms("let", &car(&list))) {
+        let value = try_result!(vm.inner_mut().eval(form));
+        bindings.extend(try_result!(destructure(&pattern, value)));
+    }
+    let environment = Environment::with_parent(&vm.symbols.environment());
+    for (symbol, value) in bindings {
+        environment.define(&symbol, &Sym::Value(value));
+    }
+    Ok(try_result!(vm.inner_mut().eval_body_within(environment, cdr(&list))))
+}
+
+/// `(let* ((name value) ...) body...)` binds sequentially so each
+/// value sees the bindings before it
+pub fn let_star<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let mut environment = vm.symbols.environment();
+    for (pattern, form) in try_result!(binding_forms("let*", &car(&list))) {
+        let previous = vm.inner_mut().symbols.enter(environment.clone());
+        let value = vm.inner_mut().eval(form);
+        vm.inner_mut().symbols.leave(previous);
+        environment = Environment::with_parent(&environment);
+        for (symbol, value) in try_result!(destructure(&pattern, try_result!(value))) {
+            environment.define(&symbol, &Sym::Value(value));
+        }
+    }
+    Ok(try_result!(vm.inner_mut().eval_body_within(environment, cdr(&list))))
+}
+
+/// `(letrec ((name value) ...) body...)` evaluates every value with
+/// all names already in scope, allowing mutually recursive lambdas
+pub fn letrec<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let environment = Environment::with_parent(&vm.symbols.environment());
+    let forms = try_result!(binding_forms("letrec", &car(&list)));
+    for (pattern, _) in &forms {
+        for symbol in pattern_symbols(pattern) {
+            environment.define(&symbol, &Sym::Value(Value::nil()));
+        }
+    }
+    let previous = vm.inner_mut().symbols.enter(environment.clone());
+    let mut bind = || -> Result<()> {
+        for (pattern, form) in forms {
+            let value = try_result!(vm.inner_mut().eval(form));
+            for (symbol, value) in try_result!(destructure(&pattern, value)) {
+                environment.define(&symbol, &Sym::Value(value));
+            }
+        }
+        Ok(())
+    };
+    let bound = bind();
+    vm.inner_mut().symbols.leave(previous);
+    try_result!(bound);
+    Ok(try_result!(vm.inner_mut().eval_body_within(environment, cdr(&list))))
+}
+
+/// `(flet ((name (args...) body...) ...) body...)` defines local
+/// functions whose bodies see the enclosing scope but not each other
+pub fn flet<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let enclosing = vm.symbols.environment();
+    let environment = Environment::with_parent(&enclosing);
+    try_result!(define_local_functions(vm.clone(), "flet", &car(&list), &environment, &enclosing));
+    Ok(try_result!(vm.inner_mut().eval_body_within(environment, cdr(&list))))
+}
+
+/// `(labels ((name (args...) body...) ...) body...)` is like `flet`
+/// but the local functions can call themselves and each other
+pub fn labels<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let environment = Environment::with_parent(&vm.symbols.environment());
+    try_result!(define_local_functions(
+        vm.clone(),
+        "labels",
+        &car(&list),
+        &environment,
+        &environment
+    ));
+    Ok(try_result!(vm.inner_mut().eval_body_within(environment, cdr(&list))))
+}
+
+fn define_local_functions<'c>(
+    vm: Pointer<Context<'c>>,
+    form: &str,
+    definitions: &Value<'c>,
+    environment: &Environment<'c>,
+    closure: &Environment<'c>,
+) -> Result<()> {
+    for definition in definitions.values() {
+        let name = car(&definition);
+        if !matches!(name, Value::Symbol(_)) || definition.len() < 2 {
+            return Err(runtime_error(
+                format!("malformed {} definition: {}", form, definition),
+                None,
+            ));
+        }
+        let name = name.as_symbol();
+        let function = vm.closure(
+            &name,
+            car(&cdr(&definition)),
+            cdr(&cdr(&definition)),
+            closure.clone(),
+        );
+        environment.define(&name, &Sym::Function(function));
+    }
+    Ok(())
+}
+
+/// splits the bindings of a `let` form into patterns and value
+/// forms, a bare symbol being bound to nil
+fn binding_forms<'c>(form: &str, bindings: &Value<'c>) -> Result<Vec<(Value<'c>, Value<'c>)>> {
+    let mut forms = Vec::<(Value<'c>, Value<'c>)>::new();
+    for binding in bindings.values() {
+        match &binding {
+            Value::Symbol(_) => forms.push((binding.clone(), Value::nil())),
+            Value::List(_) if binding.len() <= 2 =>
+                forms.push((car(&binding), car(&cdr(&binding)))),
+            _ => {
+                return Err(runtime_error(
+                    format!("malformed {} binding: {}", form, binding),
+                    None,
+                ))
+            },
+        }
+    }
+    Ok(forms)
+}
+
+/// matches `value` against `pattern`, a symbol or a possibly nested
+/// list of symbols optionally ending in `&rest name`
+fn destructure<'c>(pattern: &Value<'c>, value: Value<'c>) -> Result<Vec<(Symbol<'c>, Value<'c>)>> {
+    match pattern {
+        Value::Symbol(symbol) => Ok(vec![(symbol.clone(), value)]),
+        Value::List(_) => {
+            let patterns = pattern.values();
+            let values = value.values();
+            let mut bindings = Vec::<(Symbol<'c>, Value<'c>)>::new();
+            let mut index = 0;
+            while index < patterns.len() {
+                if is_rest(&patterns[index]) {
+                    let rest = values.get(index..).unwrap_or_default().to_vec();
+                    let rest = if rest.is_empty() { Value::nil() } else { Value::from_iter(rest) };
+                    return match patterns.get(index + 1) {
+                        Some(pattern) => {
+                            bindings.extend(try_result!(destructure(pattern, rest)));
+                            Ok(bindings)
+                        },
+                        None => Err(runtime_error(
+                            format!("&rest without a name in pattern {}", pattern),
+                            None,
+                        )),
+                    };
+                }
+                match values.get(index) {
+                    Some(value) =>
+                        bindings.extend(try_result!(destructure(&patterns[index], value.clone()))),
+                    None => {
+                        return Err(runtime_error(
+                            format!("not enough values to destructure {} from {}", pattern, value),
+                            None,
+                        ))
+                    },
+                }
+                index += 1;
+            }
+            if values.len() > patterns.len() {
+                return Err(runtime_error(
+                    format!("too many values to destructure {} from {}", pattern, value),
+                    None,
+                ));
+            }
+            Ok(bindings)
+        },
+        _ => Err(runtime_error(format!("cannot bind {}", pattern), None)),
+    }
+}
+
+fn pattern_symbols<'c>(pattern: &Value<'c>) -> Vec<Symbol<'c>> {
+    match pattern {
+        Value::Symbol(symbol) if !is_rest(pattern) => vec![symbol.clone()],
+        Value::List(_) => pattern.values().iter().flat_map(pattern_symbols).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn is_rest(pattern: &Value) -> bool {
+    matches!(pattern, Value::Symbol(symbol) if ["&rest", "&body"].contains(&symbol.symbol()))
+}
@@ -0,0 +1,131 @@
+use sexprs_data_structures::{car, cdr, AsSymbol, Quotable, Symbol, Value};
+use sexprs_util::try_result;
+
+use crate::builtin::string::format::format;
+use crate::helpers::runtime_error;
+use crate::{Condition, Context, Environment, ErrorType, Pointer, Result, Sym};
+
+/// `(signal 'symbol data)` signals a condition of type `symbol`
+pub fn signal<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let args = try_result!(vm.inner_mut().eval_arguments(list));
+    let symbol = car(&args);
+    if !matches!(symbol, Value::Symbol(_) | Value::QuotedSymbol(_)) {
+        return Err(runtime_error(format!("signal invoked with non-symbol: {}", symbol), None));
+    }
+    let data = Quotable::unquote(&car(&cdr(&args)));
+    Err(vm.signal(Condition::new(symbol.as_symbol().unquote(), data)))
+}
+
+/// `(error "template" args...)` signals an `error` condition whose
+/// data is the message made by `(format "template" args...)`
+pub fn error<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let message = try_result!(format(vm.clone(), list));
+    Err(vm.signal(Condition::new(
+        Symbol::new(ErrorType::RuntimeError.condition_symbol()),
+        Value::from_iter([message]),
+    )))
+}
+
+/// `(condition-case var bodyform (condition body...) ...)` evaluates
+/// `bodyform` and, if it signals a condition that a handler names,
+/// evaluates that handler with `var` bound to `(condition . data)`.
+/// The condition `error` and `t` handle every condition.
+pub fn condition_case<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let var = car(&list);
+    let error = match vm.inner_mut().eval(car(&cdr(&list))) {
+        Ok(value) => return Ok(value),
+        Err(error) if error.ty() == ErrorType::NoCatch => return Err(error),
+        Err(error) => error,
+    };
+    let condition = vm.condition_of(&error);
+    for handler in cdr(&cdr(&list)).values() {
+        if !handles(&car(&handler), &condition) {
+            continue;
+        }
+        let condition = vm.take_condition(&error);
+        let environment = Environment::with_parent(&vm.symbols.environment());
+        if var.is_symbol() && !var.is_nil() {
+            environment.define(&var.as_symbol(), &Sym::Value(condition.as_value()));
+        }
+        return Ok(try_result!(vm.inner_mut().eval_body_within(environment, cdr(&handler))));
+    }
+    Err(error)
+}
+
+/// `(unwind-protect bodyform cleanup...)` evaluates the cleanup forms
+/// however `bodyform` exits and returns the result of `bodyform`
+pub fn unwind_protect<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let result = vm.inner_mut().eval(car(&list));
+    let thrown = vm.thrown.clone();
+    let backtrace = vm.backtrace.clone();
+    try_result!(vm.inner_mut().eval_body(cdr(&list)));
+    vm.inner_mut().thrown = thrown;
+    vm.inner_mut().backtrace = backtrace;
+    result
+}
+
+/// `(ignore-errors body...)` returns nil if `body` signals
+pub fn ignore_errors<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    match vm.inner_mut().eval_body(list) {
+        Err(error) if error.ty() != ErrorType::NoCatch => {
+            vm.take_condition(&error);
+            Ok(Value::nil())
+        },
+        result => result,
+    }
+}
+
+/// `(catch tag body...)` returns the value thrown to `tag` from
+/// within `body`, or the value of `body`
+pub fn catch<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let tag = Quotable::unquote(&try_result!(vm.inner_mut().eval(car(&list))));
+    match vm.inner_mut().eval_body(cdr(&list)) {
+        Err(error) if error.ty() == ErrorType::NoCatch => match vm.thrown.clone() {
+            Some((thrown, value)) if Quotable::unquote(&thrown) == tag => {
+                vm.inner_mut().thrown = None;
+                vm.inner_mut().backtrace = None;
+                Ok(value)
+            },
+            _ => Err(error),
+        },
+        result => result,
+    }
+}
+
+/// `(throw tag value)` unwinds to the innermost `catch` of `tag`
+pub fn throw<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let tag = try_result!(vm.inner_mut().eval(car(&list)));
+    let value = try_result!(vm.inner_mut().eval(car(&cdr(&list))));
+    Err(vm.throw(tag, value))
+}
+
+fn handles(spec: &Value, condition: &Condition) -> bool {
+    match spec {
+        Value::T => true,
+        Value::Symbol(symbol) =>
+            symbol.symbol() == "error" || symbol.symbol() == condition.symbol.symbol(),
+        Value::List(_) => spec.values().iter().any(|spec| handles(spec, condition)),
+        _ => false,
+    }
+}
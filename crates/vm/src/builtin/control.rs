@@ -0,0 +1,195 @@
+use sexprs_data_structures::{car, cdr, AsSymbol, BigInt, Quotable, Value};
+use sexprs_util::try_result;
+
+use crate::builtin::math::Number;
+use crate::helpers::runtime_error;
+use crate::{Context, Environment, Pointer, Result, Sym};
+
+pub fn progn<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    Ok(try_result!(vm.inner_mut().eval_tail_body(list)))
+}
+
+pub fn prog1<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let value = try_result!(vm.inner_mut().eval(car(&list)));
+    try_result!(vm.inner_mut().eval_body(cdr(&list)));
+    Ok(value)
+}
+
+/// `(cond (test body...) ...)` evaluates the body of the first clause
+/// whose test is non-nil, or returns the test value when the body is
+/// empty
+pub fn cond<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    for clause in list.values() {
+        if !clause.is_list() {
+            return Err(runtime_error(format!("malformed cond clause: {}", clause), None));
+        }
+        let test = try_result!(vm.inner_mut().eval(car(&clause)));
+        if truthy(test.clone()) {
+            let body = cdr(&clause);
+            return if body.is_empty() {
+                Ok(test)
+            } else {
+                Ok(try_result!(vm.inner_mut().eval_tail_body(body)))
+            };
+        }
+    }
+    Ok(Value::nil())
+}
+
+/// `(and forms...)` stops at the first nil value, returning the last
+/// value otherwise
+pub fn and<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let mut forms = list.values();
+    let last = match forms.pop() {
+        Some(last) => last,
+        None => return Ok(Value::T),
+    };
+    for form in forms {
+        if !truthy(try_result!(vm.inner_mut().eval(form))) {
+            return Ok(Value::nil());
+        }
+    }
+    Ok(try_result!(vm.inner_mut().eval_tail(last)))
+}
+
+/// `(or forms...)` returns the first non-nil value
+pub fn or<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let mut forms = list.values();
+    let last = match forms.pop() {
+        Some(last) => last,
+        None => return Ok(Value::nil()),
+    };
+    for form in forms {
+        let value = try_result!(vm.inner_mut().eval(form));
+        if truthy(value.clone()) {
+            return Ok(value);
+        }
+    }
+    Ok(try_result!(vm.inner_mut().eval_tail(last)))
+}
+
+/// `(case key (keys body...) ... (otherwise body...))` compares the
+/// value of `key` with the unevaluated keys of each clause using
+/// `eql`. A clause whose keys are `nil` or `()` never matches.
+pub fn case<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let key = Quotable::unquote(&try_result!(vm.inner_mut().eval(car(&list))));
+    for clause in cdr(&list).values() {
+        if !clause.is_list() {
+            return Err(runtime_error(format!("malformed case clause: {}", clause), None));
+        }
+        let keys = car(&clause);
+        let matches = match &keys {
+            Value::T => true,
+            Value::Symbol(symbol) if symbol.symbol() == "otherwise" => true,
+            _ if keys.is_nil() => false,
+            Value::List(_) | Value::QuotedList(_) =>
+                keys.values().iter().any(|candidate| Quotable::unquote(candidate).is_eql(&key)),
+            candidate => Quotable::unquote(candidate).is_eql(&key),
+        };
+        if matches {
+            return Ok(try_result!(vm.inner_mut().eval_tail_body(cdr(&clause))));
+        }
+    }
+    Ok(Value::nil())
+}
+
+/// `(while test body...)` evaluates `body` for as long as `test` is
+/// non-nil and returns nil
+pub fn r#while<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let test = car(&list);
+    let body = cdr(&list);
+    while truthy(try_result!(vm.inner_mut().eval(test.clone()))) {
+        try_result!(vm.inner_mut().eval_body(body.clone()));
+    }
+    Ok(Value::nil())
+}
+
+/// `(dolist (var list [result]) body...)` binds `var` to each element
+/// of `list` in a fresh frame per iteration
+pub fn dolist<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let (var, items, result) = try_result!(loop_spec("dolist", &car(&list)));
+    let items = try_result!(vm.inner_mut().eval(items));
+    let body = cdr(&list);
+    for item in items.values() {
+        try_result!(eval_iteration(vm.clone(), &var, item, body.clone()));
+    }
+    Ok(try_result!(eval_iteration(vm, &var, Value::nil(), Value::from_iter([result]))))
+}
+
+/// `(dotimes (var count [result]) body...)` binds `var` from 0 below
+/// `count` in a fresh frame per iteration
+pub fn dotimes<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let (var, count, result) = try_result!(loop_spec("dotimes", &car(&list)));
+    let count = match try_result!(vm.inner_mut().eval(count)) {
+        Value::UnsignedInteger(count) => u64::from(*count),
+        Value::Integer(count) => u64::try_from(*count).unwrap_or_default(),
+        count => {
+            return Err(runtime_error(
+                format!("dotimes count must be an integer: {}", count),
+                None,
+            ))
+        },
+    };
+    let body = cdr(&list);
+    for index in 0..count {
+        let index = Number::from_integer(BigInt::from(index), 0).to_value();
+        try_result!(eval_iteration(vm.clone(), &var, index, body.clone()));
+    }
+    let count = Number::from_integer(BigInt::from(count), 0).to_value();
+    Ok(try_result!(eval_iteration(vm, &var, count, Value::from_iter([result]))))
+}
+
+fn eval_iteration<'c>(
+    mut vm: Pointer<Context<'c>>,
+    var: &Value<'c>,
+    value: Value<'c>,
+    body: Value<'c>,
+) -> Result<Value<'c>> {
+    let environment = Environment::with_parent(&vm.symbols.environment());
+    environment.define(&var.as_symbol(), &Sym::Value(value));
+    let previous = vm.inner_mut().symbols.enter(environment);
+    let value = vm.inner_mut().eval_body(body);
+    vm.inner_mut().symbols.leave(previous);
+    Ok(try_result!(value))
+}
+
+fn loop_spec<'c>(form: &str, spec: &Value<'c>) -> Result<(Value<'c>, Value<'c>, Value<'c>)> {
+    let values = spec.values();
+    match values.as_slice() {
+        [var @ Value::Symbol(_), value] => Ok((var.clone(), value.clone(), Value::nil())),
+        [var @ Value::Symbol(_), value, result] =>
+            Ok((var.clone(), value.clone(), result.clone())),
+        _ => Err(runtime_error(format!("malformed {} spec: {}", form, spec), None)),
+    }
+}
+
+fn truthy(value: Value) -> bool {
+    value.into()
+}
@@ -0,0 +1,33 @@
+use sexprs_data_structures::Value;
+use sexprs_util::try_result;
+
+use super::arguments;
+use crate::{Context, Pointer, Result};
+
+/// `(eq a b)` is t when `a` and `b` are the same object, see
+/// [`Value::is_eq`]
+pub fn eq<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    compare(vm, "eq", list, Value::is_eq)
+}
+
+/// `(eql a b)` is t when `a` and `b` are `eq` or numbers of the same
+/// type and value, see [`Value::is_eql`]
+pub fn eql<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    compare(vm, "eql", list, Value::is_eql)
+}
+
+/// `(equal a b)` is t when `a` and `b` have the same structure and
+/// contents, see [`Value::is_equal`]
+pub fn equal<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    compare(vm, "equal", list, Value::is_equal)
+}
+
+fn compare<'c>(
+    vm: Pointer<Context<'c>>,
+    name: &str,
+    list: Value<'c>,
+    predicate: fn(&Value<'c>, &Value<'c>) -> bool,
+) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, name, list, 2, Some(2)));
+    Ok(predicate(&arguments[0], &arguments[1]).into())
+}
@@ -0,0 +1,114 @@
+use sexprs_data_structures::{BigInt, HashTable, HashTableTest, Value};
+use sexprs_util::{try_result, with_caller};
+
+use super::arguments;
+use super::math::Number;
+use crate::{runtime_error, Context, Pointer, Result};
+
+/// `(make-hash-table &rest keywords)` returns an empty hash table
+/// comparing keys with the `:test`, one of `eq`, `eql` (the default)
+/// or `equal`. `:size` is accepted and ignored.
+pub fn make_hash_table<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "make-hash-table", list, 0, None));
+    let mut test = HashTableTest::default();
+    let mut arguments = arguments.into_iter();
+    while let Some(keyword) = arguments.next() {
+        let value = match arguments.next() {
+            Some(value) => value,
+            None => {
+                return Err(with_caller!(runtime_error(
+                    format!("make-hash-table called without a value for: {}", keyword),
+                    None
+                )))
+            },
+        };
+        match keyword.to_string().as_str() {
+            ":test" => test = try_result!(test_argument(&value)),
+            ":size" => {},
+            _ => {
+                return Err(with_caller!(runtime_error(
+                    format!("make-hash-table called with unknown keyword: {}", keyword),
+                    None
+                )))
+            },
+        }
+    }
+    Ok(Value::HashTable(HashTable::new(test)))
+}
+
+/// `(gethash key table &optional default)` returns the value of `key`
+/// in `table`, or `default` when it is not there
+pub fn gethash<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "gethash", list, 2, Some(3)));
+    let table = try_result!(hash_table_argument("gethash", &arguments[1]));
+    Ok(table
+        .get(&arguments[0])
+        .unwrap_or_else(|| arguments.get(2).cloned().unwrap_or_default()))
+}
+
+/// `(puthash key value table)` associates `value` with `key` in
+/// `table` and returns `value`
+pub fn puthash<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "puthash", list, 3, Some(3)));
+    let table = try_result!(hash_table_argument("puthash", &arguments[2]));
+    table.insert(arguments[0].clone(), arguments[1].clone());
+    Ok(arguments[1].clone())
+}
+
+/// `(remhash key table)` removes `key` from `table`
+pub fn remhash<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "remhash", list, 2, Some(2)));
+    let table = try_result!(hash_table_argument("remhash", &arguments[1]));
+    table.remove(&arguments[0]);
+    Ok(Value::nil())
+}
+
+/// `(maphash function table)` calls `function` with each key and
+/// value of `table` in insertion order
+pub fn maphash<'c>(mut vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "maphash", list, 2, Some(2)));
+    let table = try_result!(hash_table_argument("maphash", &arguments[1]));
+    for (key, value) in table.entries() {
+        try_result!(vm.inner_mut().funcall(arguments[0].clone(), Value::from_iter([key, value])));
+    }
+    Ok(Value::nil())
+}
+
+/// `(hash-table-count table)` counts the entries of `table`
+pub fn hash_table_count<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "hash-table-count", list, 1, Some(1)));
+    let table = try_result!(hash_table_argument("hash-table-count", &arguments[0]));
+    Ok(Number::from_integer(BigInt::from(table.len()), 0).to_value())
+}
+
+/// `(hash-table-keys table)` returns the keys of `table` in insertion
+/// order
+pub fn hash_table_keys<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "hash-table-keys", list, 1, Some(1)));
+    let table = try_result!(hash_table_argument("hash-table-keys", &arguments[0]));
+    Ok(Value::from_iter(table.keys()))
+}
+
+fn hash_table_argument<'a, 'c>(name: &str, value: &'a Value<'c>) -> Result<&'a HashTable<'c>> {
+    match value {
+        Value::HashTable(table) => Ok(table),
+        value => Err(with_caller!(runtime_error(
+            format!("{} called with non-hash-table value: {}", name, value),
+            None
+        ))),
+    }
+}
+
+fn test_argument(value: &Value) -> Result<HashTableTest> {
+    let test = match value {
+        Value::Symbol(symbol) | Value::QuotedSymbol(symbol) => HashTableTest::from_name(symbol.symbol()),
+        _ => None,
+    };
+    match test {
+        Some(test) => Ok(test),
+        None => Err(with_caller!(runtime_error(
+            format!("make-hash-table called with invalid test: {}", value),
+            None
+        ))),
+    }
+}
@@ -1,11 +1,10 @@
 #![allow(unused)]
 use sexprs_data_structures::Value;
 
-use crate::{Result, Context};
-use unique_pointer::UniquePointer;
+use crate::{Context, Pointer, Result};
 
 pub fn t<'c>(
-    mut vm: UniquePointer<Context<'c>>,
+    mut vm: Pointer<Context<'c>>,
     list: Value<'c>,
 ) -> Result<Value<'c>> {
     Ok(Value::T)
@@ -1,25 +1,27 @@
 use sexprs_data_structures as ds;
-use sexprs_data_structures::{AsCell, Quotable, Value};
+use sexprs_data_structures::{Cell, Quotable, Value};
 use sexprs_util::try_result;
-use unique_pointer::UniquePointer;
 
+use super::arguments;
 use crate::helpers::runtime_error;
-use crate::{Context, Result};
+use crate::{Context, Pointer, Result};
 
-pub fn list<'c>(vm: UniquePointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
-    Ok(ds::list(try_result!(vm.clone().eval(list))))
+pub fn list<'c>(mut vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let mut cell = Cell::nil();
+    for value in list.values() {
+        cell.push_value(try_result!(vm.inner_mut().eval(value)));
+    }
+    Ok(Value::list(cell))
 }
 
-pub fn cons<'c>(_vm: UniquePointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
-    let cell = ds::cons(ds::car(&list), &mut ds::cdr(&list).as_cell());
-    Ok(if list.is_quoted() {
-        Value::quoted_list(cell)
-    } else {
-        Value::list(cell)
-    })
+pub fn cons<'c>(mut vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let args = try_result!(vm.inner_mut().eval_arguments(list));
+    let head = ds::car(&args);
+    let tail = ds::car(&ds::cdr(&args));
+    Ok(ds::pair(head, tail))
 }
 pub fn quote<'c>(
-    _vm: UniquePointer<Context<'c>>,
+    _vm: Pointer<Context<'c>>,
     value: Value<'c>,
 ) -> Result<Value<'c>> {
     Ok(match &value {
@@ -35,22 +37,149 @@ pub fn quote<'c>(
     })
 }
 pub fn backquote<'c>(
-    _vm: UniquePointer<Context<'c>>,
+    vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    Ok(try_result!(quasiquote(vm, list.head(), 1)).quote())
+}
+
+pub fn unquote<'c>(
+    _vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    Err(runtime_error(format!("unquote outside of backquote: ,{}", list.head()), None))
+}
+
+pub fn unquote_splicing<'c>(
+    _vm: Pointer<Context<'c>>,
     list: Value<'c>,
 ) -> Result<Value<'c>> {
-    Ok(list)
+    Err(runtime_error(
+        format!("unquote-splicing outside of backquote: ,@{}", list.head()),
+        None,
+    ))
+}
+
+/// expands the backquote template `form` at nesting `depth`,
+/// evaluating `(unquote ...)` and splicing `(unquote-splicing ...)`
+/// forms that belong to the innermost backquote.
+fn quasiquote<'c>(
+    mut vm: Pointer<Context<'c>>,
+    form: Value<'c>,
+    depth: usize,
+) -> Result<Value<'c>> {
+    if !form.is_list() {
+        return Ok(form);
+    }
+    if let Some((operator, argument)) = quasiquote_operator(&form) {
+        return match (operator, depth) {
+            ("unquote", 1) => Ok(try_result!(vm.inner_mut().eval(argument))),
+            ("unquote-splicing", 1) => Err(runtime_error(
+                format!("unquote-splicing outside of a list: {}", form),
+                None,
+            )),
+            ("backquote", _) => Ok(Value::from_iter([
+                Value::symbol(operator),
+                try_result!(quasiquote(vm, argument, depth + 1)),
+            ])),
+            _ => Ok(Value::from_iter([
+                Value::symbol(operator),
+                try_result!(quasiquote(vm, argument, depth - 1)),
+            ])),
+        };
+    }
+    let mut items = Vec::<Value<'c>>::new();
+    let mut rest = form.clone();
+    let cdr = loop {
+        let cell = match &rest {
+            Value::List(cell) | Value::QuotedList(cell) if !cell.is_nil() => cell.clone(),
+            _ => break rest,
+        };
+        // `(a . ,b) reads as (a unquote b), whose cdr is the unquote form
+        if quasiquote_operator(&rest).is_some() {
+            break try_result!(quasiquote(vm.clone(), rest, depth));
+        }
+        let item = cell.head().unwrap_or_default();
+        match quasiquote_operator(&item) {
+            Some(("unquote-splicing", argument)) if depth == 1 => {
+                let spliced = try_result!(vm.inner_mut().eval(argument));
+                if spliced.is_list() {
+                    items.extend(spliced.values());
+                } else if !spliced.is_empty() {
+                    return Err(runtime_error(
+                        format!("unquote-splicing of non-list value: {}", spliced),
+                        None,
+                    ));
+                }
+            },
+            _ => items.push(try_result!(quasiquote(vm.clone(), item, depth))),
+        }
+        rest = cell.cdr();
+    };
+    let value = ds::append([Value::from_iter(items), cdr]);
+    Ok(if form.is_quoted() { value.quote() } else { value })
+}
+
+fn quasiquote_operator<'c>(form: &Value<'c>) -> Option<(&'static str, Value<'c>)> {
+    match form {
+        Value::List(_) if form.len() == 2 => match form.head() {
+            Value::Symbol(ref symbol) => ["backquote", "unquote", "unquote-splicing"]
+                .into_iter()
+                .find(|operator| *operator == symbol.symbol())
+                .map(|operator| (operator, form.values()[1].clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn car<'c>(mut vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let args = try_result!(vm.inner_mut().eval_arguments(list));
+    Ok(args.head().head())
+}
+
+pub fn cdr<'c>(mut vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let args = try_result!(vm.inner_mut().eval_arguments(list));
+    Ok(match &args.head() {
+        Value::List(cell) | Value::QuotedList(cell) => match cell.tail().filter(|tail| !tail.is_nil()) {
+            Some(tail) => Value::List(tail.clone()),
+            None => cell.dotted().unwrap_or_default(),
+        },
+        _ => Value::Nil,
+    })
+}
+
+/// `(setcar cell object)` replaces the car of `cell` with `object`
+/// and returns `object`. Every reference to `cell` sees the change.
+pub fn setcar<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "setcar", list, 2, Some(2)));
+    let mut cell = try_result!(cons_argument("setcar", &arguments[0]));
+    cell.set_car(arguments[1].clone());
+    Ok(arguments[1].clone())
 }
 
-pub fn car<'c>(_vm: UniquePointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
-    Ok(ds::car(&ds::append(list)))
+/// `(setcdr cell object)` replaces the cdr of `cell` with `object`
+/// and returns `object`. Every reference to `cell` sees the change.
+pub fn setcdr<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "setcdr", list, 2, Some(2)));
+    let mut cell = try_result!(cons_argument("setcdr", &arguments[0]));
+    cell.set_cdr(arguments[1].clone());
+    Ok(arguments[1].clone())
 }
 
-pub fn cdr<'c>(_vm: UniquePointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
-    Ok(ds::cdr(&ds::append(list)))
+/// the cell of `value`, which must be a non-empty list
+fn cons_argument<'c>(name: &str, value: &Value<'c>) -> Result<Cell<'c>> {
+    match value {
+        Value::List(cell) | Value::QuotedList(cell) if !cell.is_nil() => Ok(cell.clone()),
+        value => Err(runtime_error(
+            format!("{} called with a value that is not a cons cell: {}", name, value),
+            None,
+        )),
+    }
 }
 pub fn append<'c>(
-    _vm: UniquePointer<Context<'c>>,
+    mut vm: Pointer<Context<'c>>,
     list: Value<'c>,
 ) -> Result<Value<'c>> {
-    Ok(ds::append(list))
+    Ok(ds::append(try_result!(vm.inner_mut().eval_arguments(list))))
 }
@@ -1,129 +1,75 @@
 //BinaryHeap;
 
-use sexprs_data_structures::{
-    append, car, cdr, AsFloat, AsInteger, AsUnsignedInteger, Cell, Value,
-};
-use sexprs_util::{try_result, unwrap_result, with_caller};
-use unique_pointer::UniquePointer;
+use sexprs_data_structures::Value;
+use sexprs_util::{try_result, with_caller};
 
-use crate::{
-    impl_arithmetic_operation, runtime_error, unfold_numeric_values_from_cdr, Context,
-    Result,
-};
+use crate::{impl_arithmetic_operation, runtime_error, Context, Pointer, Result};
 
-impl_arithmetic_operation!(+ add);
-impl_arithmetic_operation!(-sub);
-impl_arithmetic_operation!(*mul);
-impl_arithmetic_operation!(/ div);
+impl_arithmetic_operation!(+ add, 0, 0);
+impl_arithmetic_operation!(-sub, 0, 0);
+impl_arithmetic_operation!(*mul, 1, 0);
+impl_arithmetic_operation!(/ div, 1, 1);
 
-#[macro_export]
-macro_rules! unfold_numeric_values_from_cdr {
-    (
-        $operator:expr,
-        $lifetime:lifetime,
-        $vm:expr,
-        $list:expr,Value::
-        $numeric_variant:ident,
-        $as_value_fragment_name:ident,
-        $is_value_fragment_name:ident $(,)?
-    ) => {{
-        let mut operands = Cell::nil();
-        for value in cdr(&$list).into_iter() {
-            if value.$is_value_fragment_name() {
-                operands.add(&Cell::from(value));
-            } else if value.is_list() {
-                operands.add(&Cell::from(try_result!($vm.inner_mut().eval(value))));
-            } else if value.is_symbol() {
-                operands.add(&Cell::from(try_result!($vm.inner_mut().eval(value))));
-            } else {
-                return Err(with_caller!(runtime_error(
-                    format!(
-                        "called with unexpected, non-numerical value: {:#?}",
-                        value
-                    ),
-                    None
-                )));
-            }
-        }
-        operands.into_iter().map(|value| {
-            if value.is_list() {
-                let value = unwrap_result!($vm.clone().inner_mut().eval(value));
-                let value = if value.is_list() {
-                    car(&value)
-                } else  {
-                    value
-                };
-                value
-            } else if value.is_symbol() {
-                value
-            } else {
-                value
-            }
-            .$as_value_fragment_name()
-        })
-
-    }};
+/// `(abs number)` is the magnitude of `number`
+pub fn abs<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let numbers = try_result!(super::numeric_arguments(vm, "abs", list, 1, Some(1)));
+    Ok(numbers[0].abs().to_value())
 }
 
+/// defines the builtin applying `$operator` to its arguments from the
+/// left. `$identity` is the result without arguments and the left
+/// operand of a single argument, so that `(- x)` negates `x` and
+/// `(/ x)` is its reciprocal. `$min` is the least number of arguments.
 #[macro_export]
 macro_rules! impl_arithmetic_operation {
     (
         $operator:tt
-            $function_name:ident
+            $function_name:ident,
+            $identity:literal,
+            $min:literal
     ) => {
         pub fn $function_name<'c>(
-            mut vm: UniquePointer<Context<'c>>,
+            mut vm: Pointer<Context<'c>>,
             list: Value<'c>,
         ) -> Result<Value<'c>> {
             let argcount = list.len();
-            if argcount < 2 {
+            let min: usize = $min;
+            if argcount < min {
                 return Err(with_caller!(runtime_error(
                     format!(
-                        "{:#?} takes at least 2 arguments, got: {}",
+                        "{:#?} takes at least {} arguments, got: {}",
                         stringify!($operator),
+                        min,
                         argcount
                     ),
                     None
                 )));
             }
-            match &car(&list) {
-                Value::UnsignedInteger(list_car)=> {
-                    let list_car = list_car.clone();
-                    let operands = unfold_numeric_values_from_cdr!("$operator", 'c, vm, list, Value::UnsignedInteger, as_unsigned_integer, is_unsigned_integer);
-                    Ok(Value::UnsignedInteger(operands.fold(list_car, |lhs, rhs| lhs $operator rhs)))
-                },
-                Value::Integer(list_car)=> {
-                    let list_car = list_car.clone();
-                    let operands = unfold_numeric_values_from_cdr!("$operator", 'c, vm, list, Value::Integer, as_integer, is_integer);
-                    Ok(Value::Integer(operands.fold(list_car, |lhs, rhs| lhs $operator rhs)))
-                },
-                Value::Float(list_car)=> {
-                    let list_car = list_car.clone();
-                    let operands = unfold_numeric_values_from_cdr!("$operator", 'c, vm, list, Value::Float, as_float, is_float);
-                    Ok(Value::Float(operands.fold(list_car, |lhs, rhs| lhs $operator rhs)))
-                },
-                Value::Symbol(sym) | Value::QuotedSymbol(sym) => {
-                    let list_car = car(&try_result!(vm.inner_mut().eval(Value::from(sym))));
-                    let list_cdr = cdr(&list);
-                    let args = append([list_car.clone(), list_cdr.clone()]);
-                    Ok(try_result!($function_name(vm.clone(), args)))
-                },
-                Value::List(_) | Value::QuotedList(_) => {
-                    let list_car = car(&list);
-                    let list_cdr = cdr(&list);
-                    let list_car = try_result!(vm.clone().inner_mut().eval(list_car));
-                    let args = append([list_car.clone(), list_cdr.clone()]);
-                    Ok(try_result!($function_name(vm.clone(), args)))
-                },
-                value => Err(with_caller!(runtime_error(
-                    format!(
-                        "{:#?} called with non-numerical value: {:#?}",
-                        stringify!($operator),
-                        value
-                    ),
-                    None
-                ))),
+            let operands = try_result!(vm.inner_mut().eval_arguments(list)).values();
+            let mut numbers = Vec::<$crate::builtin::math::Number>::new();
+            for value in &operands {
+                match $crate::builtin::math::Number::from_value(value) {
+                    Some(number) => numbers.push(number),
+                    None => {
+                        return Err(with_caller!(runtime_error(
+                            format!(
+                                "{:#?} called with non-numerical value: {:#?}",
+                                stringify!($operator),
+                                value
+                            ),
+                            None
+                        )))
+                    },
+                }
+            }
+            if numbers.len() < 2 {
+                numbers.insert(0, $crate::builtin::math::Number::UnsignedInteger($identity));
+            }
+            let mut result = numbers[0].clone();
+            for number in &numbers[1..] {
+                result = try_result!(result.operate(stringify!($operator), number));
             }
+            Ok(result.to_value())
         }
     };
 }
@@ -0,0 +1,61 @@
+use num_traits::ToPrimitive;
+use sexprs_data_structures::{BigInt, Value};
+use sexprs_util::try_result;
+
+use super::{integer_argument, numeric_arguments, Number};
+use crate::helpers::arithmetic_error;
+use crate::{Context, Pointer, Result};
+
+/// `(logand integers...)` is the bitwise and of two's complement
+/// integers, -1 without arguments
+pub fn logand<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    fold(vm, "logand", list, BigInt::from(-1), |lhs, rhs| lhs & rhs)
+}
+
+/// `(logior integers...)` is the bitwise inclusive or, 0 without
+/// arguments
+pub fn logior<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    fold(vm, "logior", list, BigInt::from(0), |lhs, rhs| lhs | rhs)
+}
+
+/// `(logxor integers...)` is the bitwise exclusive or, 0 without
+/// arguments
+pub fn logxor<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    fold(vm, "logxor", list, BigInt::from(0), |lhs, rhs| lhs ^ rhs)
+}
+
+/// `(ash integer count)` shifts `integer` left by `count` bits, or
+/// right with sign extension when `count` is negative
+pub fn ash<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, "ash", list, 2, Some(2)));
+    let integer = try_result!(integer_argument("ash", &numbers[0]));
+    let count = try_result!(integer_argument("ash", &numbers[1]));
+    let shifted = match count.to_i32() {
+        Some(count) if count >= 0 => integer << count as usize,
+        Some(count) => integer >> count.unsigned_abs() as usize,
+        None => {
+            return Err(arithmetic_error(format!(
+                "shift count too large: (ash {} {})",
+                numbers[0], numbers[1]
+            )))
+        },
+    };
+    Ok(Number::from_integer(shifted, numbers[0].rank()).to_value())
+}
+
+fn fold<'c>(
+    vm: Pointer<Context<'c>>,
+    name: &str,
+    list: Value<'c>,
+    identity: BigInt,
+    operation: fn(BigInt, BigInt) -> BigInt,
+) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, name, list, 0, None));
+    let mut result = identity;
+    let mut rank = 0;
+    for number in &numbers {
+        result = operation(result, try_result!(integer_argument(name, number)));
+        rank = rank.max(number.rank());
+    }
+    Ok(Number::from_integer(result, rank).to_value())
+}
@@ -0,0 +1,83 @@
+use std::cmp::Ordering;
+
+use sexprs_data_structures::Value;
+use sexprs_util::try_result;
+
+use super::{numeric_arguments, Number};
+use crate::{Context, Pointer, Result};
+
+/// `(= numbers...)` is t when all numbers are equal, whatever their
+/// types
+pub fn equal<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    monotonic(vm, "=", list, |ordering| ordering == Ordering::Equal)
+}
+
+/// `(/= numbers...)` is t when no two numbers are equal
+pub fn not_equal<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, "/=", list, 1, None));
+    for (index, lhs) in numbers.iter().enumerate() {
+        for rhs in &numbers[index + 1..] {
+            if !matches!(lhs.compare(rhs), Some(Ordering::Less | Ordering::Greater)) {
+                return Ok(Value::nil());
+            }
+        }
+    }
+    Ok(Value::T)
+}
+
+pub fn less<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    monotonic(vm, "<", list, |ordering| ordering == Ordering::Less)
+}
+
+pub fn greater<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    monotonic(vm, ">", list, |ordering| ordering == Ordering::Greater)
+}
+
+pub fn less_or_equal<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    monotonic(vm, "<=", list, |ordering| ordering != Ordering::Greater)
+}
+
+pub fn greater_or_equal<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    monotonic(vm, ">=", list, |ordering| ordering != Ordering::Less)
+}
+
+/// `(min numbers...)` returns the smallest number
+pub fn min<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    extremum(vm, "min", list, Ordering::Less)
+}
+
+/// `(max numbers...)` returns the largest number
+pub fn max<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    extremum(vm, "max", list, Ordering::Greater)
+}
+
+/// t when `accept` holds for the ordering of each number with the
+/// next, nil when a NaN is compared
+fn monotonic<'c>(
+    vm: Pointer<Context<'c>>,
+    name: &str,
+    list: Value<'c>,
+    accept: fn(Ordering) -> bool,
+) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, name, list, 1, None));
+    Ok(Value::from(numbers.windows(2).all(|pair| match pair[0].compare(&pair[1]) {
+        Some(ordering) => accept(ordering),
+        None => false,
+    })))
+}
+
+fn extremum<'c>(
+    vm: Pointer<Context<'c>>,
+    name: &str,
+    list: Value<'c>,
+    wanted: Ordering,
+) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, name, list, 1, None));
+    let mut result: &Number = &numbers[0];
+    for number in &numbers[1..] {
+        if number.compare(result) == Some(wanted) {
+            result = number;
+        }
+    }
+    Ok(result.to_value())
+}
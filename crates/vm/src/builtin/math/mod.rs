@@ -1 +1,50 @@
 pub mod arithmetic;
+pub mod bitwise;
+pub mod comparison;
+pub mod number;
+pub mod predicate;
+pub mod rounding;
+pub mod transcendental;
+pub use number::Number;
+
+use sexprs_data_structures::Value;
+use sexprs_util::{try_result, with_caller};
+
+use super::arguments;
+use crate::{runtime_error, Context, Pointer, Result};
+
+/// evaluates the arguments of the math builtin `name`, which takes
+/// at least `min` and at most `max` numbers
+pub(crate) fn numeric_arguments<'c>(
+    vm: Pointer<Context<'c>>,
+    name: &str,
+    list: Value<'c>,
+    min: usize,
+    max: Option<usize>,
+) -> Result<Vec<Number>> {
+    let mut numbers = Vec::<Number>::new();
+    for value in try_result!(arguments(vm, name, list, min, max)) {
+        match Number::from_value(&value) {
+            Some(number) => numbers.push(number),
+            None => {
+                return Err(with_caller!(runtime_error(
+                    format!("{} called with non-numerical value: {}", name, value),
+                    None
+                )))
+            },
+        }
+    }
+    Ok(numbers)
+}
+
+/// the integer value of `number` for the builtin `name`, which only
+/// accepts integers
+pub(crate) fn integer_argument(name: &str, number: &Number) -> Result<sexprs_data_structures::BigInt> {
+    match number.to_integer() {
+        Some(integer) => Ok(integer),
+        None => Err(with_caller!(runtime_error(
+            format!("{} called with non-integer value: {}", name, number),
+            None
+        ))),
+    }
+}
@@ -0,0 +1,292 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+use num_integer::Integer;
+use num_traits::{CheckedDiv, FromPrimitive, Signed, ToPrimitive, Zero};
+use sexprs_data_structures::{BigInt, BigRational, Value};
+
+use crate::helpers::arithmetic_error;
+use crate::Result;
+
+/// a number of the numeric tower: unsigned integers promote to
+/// integers, integers to big integers, big integers to ratios and
+/// ratios to floats.
+///
+/// Operations promote both operands to the higher of their types.
+/// Results that overflow their type are computed in the next exact
+/// type instead, and exact results are demoted back to the smallest
+/// integer type that holds them.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum Number {
+    UnsignedInteger(u32),
+    Integer(i64),
+    BigInt(BigInt),
+    Ratio(BigRational),
+    Float(f64),
+}
+
+impl Number {
+    pub fn from_value(value: &Value) -> Option<Number> {
+        match value {
+            Value::Byte(byte) => Some(Number::UnsignedInteger(u32::from(*byte))),
+            Value::UnsignedInteger(value) => Some(Number::UnsignedInteger(**value)),
+            Value::Integer(value) => Some(Number::Integer(**value)),
+            Value::BigInt(value) => Some(Number::BigInt(value.clone())),
+            Value::Ratio(value) => Some(Number::Ratio(value.clone())),
+            Value::Float(value) => Some(Number::Float(**value)),
+            _ => None,
+        }
+    }
+
+    pub fn to_value<'c>(&self) -> Value<'c> {
+        match self {
+            Number::UnsignedInteger(value) => Value::unsigned_integer(*value),
+            Number::Integer(value) => Value::integer(*value),
+            Number::BigInt(value) => Value::BigInt(value.clone()),
+            Number::Ratio(value) => Value::Ratio(value.clone()),
+            Number::Float(value) => Value::float(*value),
+        }
+    }
+
+    pub fn rank(&self) -> u8 {
+        match self {
+            Number::UnsignedInteger(_) => 0,
+            Number::Integer(_) => 1,
+            Number::BigInt(_) => 2,
+            Number::Ratio(_) => 3,
+            Number::Float(_) => 4,
+        }
+    }
+
+    /// this number as the type of rank `rank`
+    fn promote(&self, rank: u8) -> Number {
+        if self.rank() >= rank {
+            return self.clone();
+        }
+        let promoted = match self {
+            Number::UnsignedInteger(value) => Number::Integer(i64::from(*value)),
+            Number::Integer(value) => Number::BigInt(BigInt::from(*value)),
+            Number::BigInt(value) => Number::Ratio(BigRational::from_integer(value.clone())),
+            Number::Ratio(value) => Number::Float(value.to_f64().unwrap_or(f64::NAN)),
+            Number::Float(_) => self.clone(),
+        };
+        promoted.promote(rank)
+    }
+
+    /// applies the arithmetic `operator`, one of `+ - * /`, to this
+    /// number and `rhs`.
+    ///
+    /// Division of exact numbers yields a ratio unless exact.
+    pub fn operate(&self, operator: &str, rhs: &Number) -> Result<Number> {
+        let rank = self.rank().max(rhs.rank());
+        match (self.promote(rank), rhs.promote(rank)) {
+            (Number::UnsignedInteger(lhs), Number::UnsignedInteger(rhs)) => {
+                match checked_u32(operator, lhs, rhs) {
+                    Some(value) => Ok(Number::UnsignedInteger(value)),
+                    None => Number::Integer(i64::from(lhs))
+                        .operate(operator, &Number::Integer(i64::from(rhs))),
+                }
+            },
+            (Number::Integer(lhs), Number::Integer(rhs)) => match checked_i64(operator, lhs, rhs) {
+                Some(value) => Ok(Number::Integer(value)),
+                None => Number::BigInt(BigInt::from(lhs))
+                    .operate(operator, &Number::BigInt(BigInt::from(rhs))),
+            },
+            (Number::BigInt(lhs), Number::BigInt(rhs)) => match operator {
+                "+" => Ok(Number::BigInt(lhs + rhs).normalize()),
+                "-" => Ok(Number::BigInt(lhs - rhs).normalize()),
+                "*" => Ok(Number::BigInt(lhs * rhs).normalize()),
+                _ => Number::Ratio(BigRational::from_integer(lhs))
+                    .operate(operator, &Number::Ratio(BigRational::from_integer(rhs))),
+            },
+            (Number::Ratio(lhs), Number::Ratio(rhs)) => match operator {
+                "+" => Ok(Number::Ratio(lhs + rhs).normalize()),
+                "-" => Ok(Number::Ratio(lhs - rhs).normalize()),
+                "*" => Ok(Number::Ratio(lhs * rhs).normalize()),
+                _ => match lhs.checked_div(&rhs) {
+                    Some(value) => Ok(Number::Ratio(value).normalize()),
+                    None => Err(arithmetic_error(format!("division by zero: (/ {} 0)", lhs))),
+                },
+            },
+            (Number::Float(lhs), Number::Float(rhs)) => Ok(Number::Float(match operator {
+                "+" => lhs + rhs,
+                "-" => lhs - rhs,
+                "*" => lhs * rhs,
+                _ => lhs / rhs,
+            })),
+            (lhs, rhs) => unreachable!("{} and {} promoted to different types", lhs, rhs),
+        }
+    }
+
+    /// the integer `value` as the smallest type holding it, no
+    /// narrower than the type of rank `rank`
+    pub fn from_integer(value: BigInt, rank: u8) -> Number {
+        match (value.to_u32(), value.to_i64()) {
+            (Some(value), _) if rank == 0 => Number::UnsignedInteger(value),
+            (_, Some(value)) => Number::Integer(value),
+            _ => Number::BigInt(value),
+        }
+    }
+
+    /// the value of an integer number, `None` for ratios and floats
+    pub fn to_integer(&self) -> Option<BigInt> {
+        match self {
+            Number::UnsignedInteger(value) => Some(BigInt::from(*value)),
+            Number::Integer(value) => Some(BigInt::from(*value)),
+            Number::BigInt(value) => Some(value.clone()),
+            Number::Ratio(_) | Number::Float(_) => None,
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match self.promote(4) {
+            Number::Float(value) => value,
+            _ => f64::NAN,
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Number::Float(_))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::UnsignedInteger(value) => *value == 0,
+            Number::Integer(value) => *value == 0,
+            Number::BigInt(value) => value.is_zero(),
+            Number::Ratio(value) => value.is_zero(),
+            Number::Float(value) => *value == 0.0,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Number::UnsignedInteger(_) => false,
+            Number::Integer(value) => *value < 0,
+            Number::BigInt(value) => value.is_negative(),
+            Number::Ratio(value) => value.is_negative(),
+            Number::Float(value) => *value < 0.0,
+        }
+    }
+
+    pub fn abs(&self) -> Number {
+        match self {
+            Number::Integer(value) => match value.checked_abs() {
+                Some(value) => Number::Integer(value),
+                None => Number::BigInt(BigInt::from(*value).abs()),
+            },
+            Number::BigInt(value) => Number::BigInt(value.abs()),
+            Number::Ratio(value) => Number::Ratio(value.abs()),
+            Number::Float(value) => Number::Float(value.abs()),
+            number => number.clone(),
+        }
+    }
+
+    /// compares numbers of any type by value, `None` when either is
+    /// NaN
+    pub fn compare(&self, rhs: &Number) -> Option<Ordering> {
+        let rank = self.rank().max(rhs.rank());
+        match (self.promote(rank), rhs.promote(rank)) {
+            (Number::UnsignedInteger(lhs), Number::UnsignedInteger(rhs)) => lhs.partial_cmp(&rhs),
+            (Number::Integer(lhs), Number::Integer(rhs)) => lhs.partial_cmp(&rhs),
+            (Number::BigInt(lhs), Number::BigInt(rhs)) => lhs.partial_cmp(&rhs),
+            (Number::Ratio(lhs), Number::Ratio(rhs)) => lhs.partial_cmp(&rhs),
+            (lhs, rhs) => lhs.to_f64().partial_cmp(&rhs.to_f64()),
+        }
+    }
+
+    /// rounds this number to an integer with `mode`, one of `floor`,
+    /// `ceiling`, `round` (half to even) or `truncate`
+    pub fn round(&self, mode: &str) -> Result<Number> {
+        let rank = self.rank().min(2);
+        let value = match self {
+            Number::Ratio(value) => {
+                let floor = value.floor();
+                match mode {
+                    "floor" => floor.to_integer(),
+                    "ceiling" => value.ceil().to_integer(),
+                    "truncate" => value.trunc().to_integer(),
+                    _ => {
+                        let fraction = value - &floor;
+                        let half = BigRational::new(BigInt::from(1), BigInt::from(2));
+                        let floor = floor.to_integer();
+                        match fraction.cmp(&half) {
+                            Ordering::Less => floor,
+                            Ordering::Greater => floor + 1,
+                            Ordering::Equal if floor.is_even() => floor,
+                            Ordering::Equal => floor + 1,
+                        }
+                    },
+                }
+            },
+            Number::Float(value) => {
+                let rounded = match mode {
+                    "floor" => value.floor(),
+                    "ceiling" => value.ceil(),
+                    "truncate" => value.trunc(),
+                    _ => value.round_ties_even(),
+                };
+                match BigInt::from_f64(rounded) {
+                    Some(value) => value,
+                    None => {
+                        return Err(arithmetic_error(format!("cannot {} {}", mode, value)))
+                    },
+                }
+            },
+            number => return Ok(number.clone()),
+        };
+        Ok(Number::from_integer(value, rank))
+    }
+
+    /// an exact number as the smallest integer type holding it
+    fn normalize(self) -> Number {
+        match self {
+            Number::Ratio(value) if value.is_integer() =>
+                Number::BigInt(value.to_integer()).normalize(),
+            Number::BigInt(value) => match value.to_i64() {
+                Some(value) => Number::Integer(value),
+                None => Number::BigInt(value),
+            },
+            number => number,
+        }
+    }
+}
+
+/// `None` when the result does not fit `u32`, or for divisions that
+/// are inexact or by zero, which are left to wider types
+fn checked_u32(operator: &str, lhs: u32, rhs: u32) -> Option<u32> {
+    match operator {
+        "+" => lhs.checked_add(rhs),
+        "-" => lhs.checked_sub(rhs),
+        "*" => lhs.checked_mul(rhs),
+        _ => match lhs.checked_rem(rhs) {
+            Some(0) => lhs.checked_div(rhs),
+            _ => None,
+        },
+    }
+}
+
+/// like [`checked_u32`] for `i64`
+fn checked_i64(operator: &str, lhs: i64, rhs: i64) -> Option<i64> {
+    match operator {
+        "+" => lhs.checked_add(rhs),
+        "-" => lhs.checked_sub(rhs),
+        "*" => lhs.checked_mul(rhs),
+        _ => match lhs.checked_rem(rhs) {
+            Some(0) => lhs.checked_div(rhs),
+            _ => None,
+        },
+    }
+}
+
+impl Display for Number {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Number::UnsignedInteger(value) => write!(f, "{}", value),
+            Number::Integer(value) => write!(f, "{}", value),
+            Number::BigInt(value) => write!(f, "{}", value),
+            Number::Ratio(value) => write!(f, "{}", value),
+            Number::Float(value) => write!(f, "{}", value),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use num_integer::Integer;
+use sexprs_data_structures::Value;
+use sexprs_util::try_result;
+
+use super::{integer_argument, numeric_arguments};
+use crate::{Context, Pointer, Result};
+
+pub fn zerop<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, "zerop", list, 1, Some(1)));
+    Ok(Value::from(numbers[0].is_zero()))
+}
+
+pub fn evenp<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, "evenp", list, 1, Some(1)));
+    Ok(Value::from(try_result!(integer_argument("evenp", &numbers[0])).is_even()))
+}
+
+pub fn oddp<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, "oddp", list, 1, Some(1)));
+    Ok(Value::from(try_result!(integer_argument("oddp", &numbers[0])).is_odd()))
+}
@@ -0,0 +1,90 @@
+use num_integer::Integer;
+use sexprs_data_structures::Value;
+use sexprs_util::try_result;
+
+use super::{numeric_arguments, Number};
+use crate::helpers::arithmetic_error;
+use crate::{Context, Pointer, Result};
+
+/// `(floor number [divisor])` rounds `number / divisor` toward
+/// negative infinity
+pub fn floor<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    round_quotient(vm, "floor", list)
+}
+
+/// `(ceiling number [divisor])` rounds `number / divisor` toward
+/// positive infinity
+pub fn ceiling<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    round_quotient(vm, "ceiling", list)
+}
+
+/// `(round number [divisor])` rounds `number / divisor` to the
+/// nearest integer, halves to even
+pub fn round<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    round_quotient(vm, "round", list)
+}
+
+/// `(truncate number [divisor])` rounds `number / divisor` toward
+/// zero
+pub fn truncate<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    round_quotient(vm, "truncate", list)
+}
+
+/// `(mod number divisor)` is the remainder of `floor`, with the sign
+/// of `divisor`
+pub fn r#mod<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    remainder(vm, "mod", "floor", list)
+}
+
+/// `(rem number divisor)` is the remainder of `truncate`, with the
+/// sign of `number`
+pub fn rem<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    remainder(vm, "rem", "truncate", list)
+}
+
+fn round_quotient<'c>(
+    vm: Pointer<Context<'c>>,
+    mode: &str,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, mode, list, 1, Some(2)));
+    Ok(try_result!(quotient(mode, &numbers[0], numbers.get(1))).to_value())
+}
+
+/// `number / divisor` rounded with `mode`, as an integer of the
+/// narrowest type of the operands that holds it
+fn quotient(mode: &str, number: &Number, divisor: Option<&Number>) -> Result<Number> {
+    let rank = number.rank().max(divisor.map(Number::rank).unwrap_or_default());
+    let quotient = match divisor {
+        Some(divisor) if divisor.is_zero() => {
+            return Err(arithmetic_error(format!("division by zero: ({} {} 0)", mode, number)))
+        },
+        Some(divisor) => try_result!(number.operate("/", divisor)),
+        None => number.clone(),
+    };
+    let rounded = try_result!(quotient.round(mode));
+    Ok(match rounded.to_integer() {
+        Some(integer) => Number::from_integer(integer, rank.min(2)),
+        None => rounded,
+    })
+}
+
+fn remainder<'c>(
+    vm: Pointer<Context<'c>>,
+    name: &str,
+    mode: &str,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, name, list, 2, Some(2)));
+    let (number, divisor) = (&numbers[0], &numbers[1]);
+    if divisor.is_zero() {
+        return Err(arithmetic_error(format!("division by zero: ({} {} 0)", name, number)));
+    }
+    if let (Some(lhs), Some(rhs)) = (number.to_integer(), divisor.to_integer()) {
+        let value = if name == "mod" { lhs.mod_floor(&rhs) } else { lhs % rhs };
+        return Ok(Number::from_integer(value, number.rank().max(divisor.rank())).to_value());
+    }
+    let quotient = try_result!(quotient(mode, number, Some(divisor)));
+    let product = try_result!(divisor.operate("*", &quotient));
+    Ok(try_result!(number.operate("-", &product)).to_value())
+}
@@ -0,0 +1,111 @@
+use num_traits::ToPrimitive;
+use sexprs_data_structures::{BigRational, Value};
+use sexprs_util::try_result;
+
+use super::{numeric_arguments, Number};
+use crate::helpers::arithmetic_error;
+use crate::{Context, Pointer, Result};
+
+/// `(expt base power)` is exact when `base` is exact and `power` an
+/// integer, a float otherwise
+pub fn expt<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, "expt", list, 2, Some(2)));
+    let (base, power) = (&numbers[0], &numbers[1]);
+    let exponent = match power.to_integer() {
+        Some(exponent) if !base.is_float() => exponent,
+        _ => return Ok(Value::float(base.to_f64().powf(power.to_f64()))),
+    };
+    let exponent = match exponent.to_i32() {
+        Some(exponent) => exponent,
+        None => return Err(arithmetic_error(format!("exponent too large: (expt {} {})", base, power))),
+    };
+    if base.is_zero() && exponent < 0 {
+        return Err(arithmetic_error(format!("division by zero: (expt {} {})", base, power)));
+    }
+    let base_ratio = match base {
+        Number::Ratio(ratio) => ratio.clone(),
+        base => BigRational::from_integer(base.to_integer().unwrap_or_default()),
+    };
+    let result = base_ratio.pow(exponent);
+    Ok(if result.is_integer() {
+        Number::from_integer(result.to_integer(), base.rank().min(2)).to_value()
+    } else {
+        Value::Ratio(result)
+    })
+}
+
+pub fn sqrt<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    float_function(vm, "sqrt", list, f64::sqrt)
+}
+
+pub fn exp<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    float_function(vm, "exp", list, f64::exp)
+}
+
+/// `(log number [base])` is the natural logarithm of `number`, or
+/// its logarithm in `base`
+pub fn log<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, "log", list, 1, Some(2)));
+    let value = numbers[0].to_f64();
+    let result = match numbers.get(1) {
+        Some(base) => value.log(base.to_f64()),
+        None => value.ln(),
+    };
+    checked("log", &numbers, result)
+}
+
+pub fn sin<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    float_function(vm, "sin", list, f64::sin)
+}
+
+pub fn cos<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    float_function(vm, "cos", list, f64::cos)
+}
+
+pub fn tan<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    float_function(vm, "tan", list, f64::tan)
+}
+
+pub fn asin<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    float_function(vm, "asin", list, f64::asin)
+}
+
+pub fn acos<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    float_function(vm, "acos", list, f64::acos)
+}
+
+/// `(atan y [x])` is the arc tangent of `y`, or of `y / x` using the
+/// signs of both to pick the quadrant
+pub fn atan<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, "atan", list, 1, Some(2)));
+    let y = numbers[0].to_f64();
+    let result = match numbers.get(1) {
+        Some(x) => y.atan2(x.to_f64()),
+        None => y.atan(),
+    };
+    checked("atan", &numbers, result)
+}
+
+fn float_function<'c>(
+    vm: Pointer<Context<'c>>,
+    name: &str,
+    list: Value<'c>,
+    function: fn(f64) -> f64,
+) -> Result<Value<'c>> {
+    let numbers = try_result!(numeric_arguments(vm, name, list, 1, Some(1)));
+    checked(name, &numbers, function(numbers[0].to_f64()))
+}
+
+/// `result` as a float, or an error when `name` produced NaN from
+/// arguments outside its domain
+fn checked<'c>(name: &str, numbers: &[Number], result: f64) -> Result<Value<'c>> {
+    if result.is_nan() && !numbers.iter().any(|number| number.to_f64().is_nan()) {
+        let arguments = numbers.iter().map(Number::to_string).collect::<Vec<String>>();
+        return Err(arithmetic_error(format!(
+            "argument out of domain: ({} {})",
+            name,
+            arguments.join(" ")
+        )));
+    }
+    Ok(Value::float(result))
+}
@@ -1,15 +1,48 @@
+pub mod alist;
+pub mod binding;
+pub mod condition;
+pub mod control;
+pub mod equality;
+pub mod hash_table;
 pub mod identity;
 pub mod list;
 pub mod math;
+pub mod plist;
 pub mod r#if;
 pub mod r#type;
+pub mod sequence;
 pub mod state;
 pub mod string;
+pub mod vector;
 
 use sexprs_data_structures::Value;
-use unique_pointer::UniquePointer;
+use sexprs_util::{try_result, with_caller};
 
-use crate::{Context, Result};
+use crate::{runtime_error, Context, Pointer, Result};
 
 pub type BuiltinFunction =
-    for<'c> fn(UniquePointer<Context<'c>>, Value<'c>) -> Result<Value<'c>>;
+    for<'c> fn(Pointer<Context<'c>>, Value<'c>) -> Result<Value<'c>>;
+
+/// evaluates the arguments of the builtin `name`, which takes at
+/// least `min` and at most `max` arguments
+pub(crate) fn arguments<'c>(
+    mut vm: Pointer<Context<'c>>,
+    name: &str,
+    list: Value<'c>,
+    min: usize,
+    max: Option<usize>,
+) -> Result<Vec<Value<'c>>> {
+    let count = list.len();
+    if count < min || max.is_some_and(|max| count > max) {
+        let expected = match max {
+            Some(max) if max == min => format!("{}", min),
+            Some(max) => format!("{} to {}", min, max),
+            None => format!("at least {}", min),
+        };
+        return Err(with_caller!(runtime_error(
+            format!("{} takes {} arguments, got: {}", name, expected, count),
+            None
+        )));
+    }
+    Ok(try_result!(vm.inner_mut().eval_arguments(list)).values())
+}
@@ -0,0 +1,115 @@
+use sexprs_data_structures::{Symbol, Value};
+use sexprs_util::{try_result, with_caller};
+
+use super::alist::Test;
+use super::arguments;
+use crate::{runtime_error, Context, Pointer, Result};
+
+/// `(plist-get plist property &optional predicate)` returns the value
+/// following the first `property` of `plist` that is `eq` to
+/// `property`, or passes `predicate`
+pub fn plist_get<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "plist-get", list, 2, Some(3)));
+    let items = arguments[0].values();
+    let test = Test::new(arguments.get(2), Value::is_eq);
+    Ok(match try_result!(position(vm, &items, &arguments[1], &test)) {
+        Some(position) => items.get(position + 1).cloned().unwrap_or_default(),
+        None => Value::nil(),
+    })
+}
+
+/// `(plist-put plist property value &optional predicate)` returns
+/// `plist` with `value` following `property`, which is appended when
+/// missing. `plist` itself is left untouched, so callers keep the
+/// result as in `(setq plist (plist-put plist :key 1))`.
+pub fn plist_put<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "plist-put", list, 3, Some(4)));
+    let test = Test::new(arguments.get(3), Value::is_eq);
+    put_property(vm, &arguments[0], &arguments[1], &arguments[2], &test)
+}
+
+/// `(plist-member plist property &optional predicate)` returns the
+/// tail of `plist` starting at `property`, nil when it is missing
+pub fn plist_member<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "plist-member", list, 2, Some(3)));
+    let items = arguments[0].values();
+    let test = Test::new(arguments.get(2), Value::is_eq);
+    Ok(match try_result!(position(vm, &items, &arguments[1], &test)) {
+        Some(position) => Value::from_iter(items[position..].to_vec()),
+        None => Value::nil(),
+    })
+}
+
+/// `(get symbol property)` returns the value of `property` in the
+/// property list of `symbol`
+pub fn get<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "get", list, 2, Some(2)));
+    let symbol = try_result!(symbol_argument("get", &arguments[0]));
+    let items = vm.inner_ref().symbols.plist(&symbol).values();
+    let test = Test::new(None, Value::is_eq);
+    Ok(match try_result!(position(vm, &items, &arguments[1], &test)) {
+        Some(position) => items.get(position + 1).cloned().unwrap_or_default(),
+        None => Value::nil(),
+    })
+}
+
+/// `(put symbol property value)` sets `property` to `value` in the
+/// property list of `symbol` and returns `value`
+pub fn put<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "put", list, 3, Some(3)));
+    let symbol = try_result!(symbol_argument("put", &arguments[0]));
+    let plist = vm.inner_ref().symbols.plist(&symbol);
+    let test = Test::new(None, Value::is_eq);
+    let plist = try_result!(put_property(vm.clone(), &plist, &arguments[1], &arguments[2], &test));
+    vm.inner_ref().symbols.set_plist(&symbol, plist);
+    Ok(arguments[2].clone())
+}
+
+/// `(symbol-plist symbol)` returns the property list of `symbol`
+pub fn symbol_plist<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "symbol-plist", list, 1, Some(1)));
+    let symbol = try_result!(symbol_argument("symbol-plist", &arguments[0]));
+    Ok(vm.inner_ref().symbols.plist(&symbol))
+}
+
+/// the index of the first property of `items` matching `property`
+fn position<'c>(
+    vm: Pointer<Context<'c>>,
+    items: &[Value<'c>],
+    property: &Value<'c>,
+    test: &Test<'_, 'c>,
+) -> Result<Option<usize>> {
+    for (position, item) in items.iter().enumerate().step_by(2) {
+        if try_result!(test.matches(vm.clone(), item, property)) {
+            return Ok(Some(position));
+        }
+    }
+    Ok(None)
+}
+
+/// `plist` with `value` following `property`, see [`plist_put`]
+fn put_property<'c>(
+    vm: Pointer<Context<'c>>,
+    plist: &Value<'c>,
+    property: &Value<'c>,
+    value: &Value<'c>,
+    test: &Test<'_, 'c>,
+) -> Result<Value<'c>> {
+    let mut items = plist.values();
+    match try_result!(position(vm, &items, property, test)) {
+        Some(position) if position + 1 < items.len() => items[position + 1] = value.clone(),
+        Some(_) => items.push(value.clone()),
+        None => items.extend([property.clone(), value.clone()]),
+    }
+    Ok(Value::from_iter(items))
+}
+
+fn symbol_argument<'c>(name: &str, value: &Value<'c>) -> Result<Symbol<'c>> {
+    match value {
+        Value::Symbol(symbol) | Value::QuotedSymbol(symbol) => Ok(symbol.unquote()),
+        value => Err(with_caller!(runtime_error(
+            format!("{} called with non-symbol value: {}", name, value),
+            None
+        ))),
+    }
+}
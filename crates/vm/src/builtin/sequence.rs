@@ -0,0 +1,392 @@
+use std::cmp::Ordering;
+
+use sexprs_data_structures as ds;
+use sexprs_data_structures::{HashTable, HashTableTest, Value};
+use sexprs_util::{try_result, with_caller};
+
+use super::alist::Test;
+use super::arguments;
+use super::math::Number;
+use super::string::index_argument;
+use super::vector::integer;
+use crate::{runtime_error, Context, Pointer, Result};
+
+/// `(mapcar function sequence)` returns a list of the results of
+/// calling `function` with each element of `sequence`
+pub fn mapcar<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "mapcar", list, 2, Some(2)));
+    Ok(Value::from_iter(try_result!(map(vm, "mapcar", &arguments[0], &arguments[1]))))
+}
+
+/// `(mapc function sequence)` calls `function` with each element of
+/// `sequence` for its side effects and returns `sequence`
+pub fn mapc<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "mapc", list, 2, Some(2)));
+    try_result!(map(vm, "mapc", &arguments[0], &arguments[1]));
+    Ok(arguments[1].clone())
+}
+
+/// `(mapcan function sequence)` appends the lists returned by calling
+/// `function` with each element of `sequence`
+pub fn mapcan<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "mapcan", list, 2, Some(2)));
+    let results = try_result!(map(vm, "mapcan", &arguments[0], &arguments[1]));
+    Ok(ds::append(Value::from_iter(results)))
+}
+
+/// `(seq-filter predicate sequence)` returns a list of the elements
+/// of `sequence` for which `predicate` returns non-nil
+pub fn seq_filter<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    filter(vm, "seq-filter", list, true)
+}
+
+/// `(remove-if predicate sequence)` returns a list of the elements of
+/// `sequence` for which `predicate` returns nil
+pub fn remove_if<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    filter(vm, "remove-if", list, false)
+}
+
+/// `(reduce function sequence &key initial-value)` combines the
+/// elements of `sequence` from the left by calling `function` with the
+/// result so far and the next element, starting with `:initial-value`
+/// or the first element. `function` is called without arguments when
+/// there is neither.
+pub fn reduce<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "reduce", list, 2, None));
+    let mut initial = None;
+    let mut keywords = arguments[2..].iter();
+    while let Some(keyword) = keywords.next() {
+        match (keyword.to_string().as_str(), keywords.next()) {
+            (":initial-value", Some(value)) => initial = Some(value.clone()),
+            _ => {
+                return Err(with_caller!(runtime_error(
+                    format!("reduce called with invalid keyword arguments: {}", keyword),
+                    None
+                )))
+            },
+        }
+    }
+    let mut items = try_result!(elements("reduce", &arguments[1])).into_iter();
+    let initial = match initial.or_else(|| items.next()) {
+        Some(initial) => initial,
+        None => return call(vm, &arguments[0], Vec::new()),
+    };
+    fold_items(vm, &arguments[0], initial, items)
+}
+
+/// `(fold function initial sequence)` combines the elements of
+/// `sequence` from the left by calling `function` with the result so
+/// far, starting with `initial`, and the next element
+pub fn fold<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "fold", list, 3, Some(3)));
+    let items = try_result!(elements("fold", &arguments[2]));
+    fold_items(vm, &arguments[0], arguments[1].clone(), items.into_iter())
+}
+
+/// `(sort sequence predicate)` sorts `sequence` so that `predicate`
+/// called with two elements returns non-nil when the first belongs
+/// before the second. The sort is stable. Lists are sorted into a new
+/// list and vectors in place.
+pub fn sort<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "sort", list, 2, Some(2)));
+    match &arguments[0] {
+        Value::Vector(vector) => {
+            let sorted = try_result!(merge_sort(vm, vector.to_vec(), &arguments[1]));
+            for (index, item) in sorted.into_iter().enumerate() {
+                vector.set(index, item);
+            }
+            Ok(arguments[0].clone())
+        },
+        value if value.is_list() || value.is_empty() =>
+            Ok(Value::from_iter(try_result!(merge_sort(vm, value.values(), &arguments[1])))),
+        value => Err(with_caller!(runtime_error(
+            format!("sort called with non-list and non-vector value: {}", value),
+            None
+        ))),
+    }
+}
+
+/// `(reverse sequence)` returns the elements of `sequence` in reverse
+/// order, as a sequence of the same type
+pub fn reverse<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "reverse", list, 1, Some(1)));
+    Ok(match &arguments[0] {
+        Value::String(string) => Value::from(string.chars().rev().collect::<String>()),
+        Value::Bytes(bytes) => Value::bytes(bytes.to_vec().into_iter().rev().collect::<Vec<u8>>()),
+        sequence => same_type(sequence, try_result!(elements("reverse", sequence)).into_iter().rev().collect()),
+    })
+}
+
+/// `(nth n list)` returns the element of `list` at index `n`
+pub fn nth<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "nth", list, 2, Some(2)));
+    let n = try_result!(index_argument("nth", &arguments[0]));
+    Ok(nthcdr_of(n, &arguments[1]).head())
+}
+
+/// `(nthcdr n list)` returns the tail of `list` after dropping `n`
+/// elements, sharing its cells with `list`
+pub fn nthcdr<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "nthcdr", list, 2, Some(2)));
+    let n = try_result!(index_argument("nthcdr", &arguments[0]));
+    Ok(nthcdr_of(n, &arguments[1]))
+}
+
+/// `(last list &optional n)` returns the tail of `list` made of its
+/// last `n` elements, by default one
+pub fn last<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "last", list, 1, Some(2)));
+    let n = try_result!(optional_count("last", arguments.get(1)));
+    let length = arguments[0].values().len() as i64;
+    Ok(nthcdr_of(length - n, &arguments[0]))
+}
+
+/// `(butlast list &optional n)` returns a list of the elements of
+/// `list` but its last `n`, by default one
+pub fn butlast<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "butlast", list, 1, Some(2)));
+    let n = try_result!(optional_count("butlast", arguments.get(1)));
+    let mut items = arguments[0].values();
+    items.truncate(items.len().saturating_sub(usize::try_from(n).unwrap_or_default()));
+    Ok(Value::from_iter(items))
+}
+
+/// `(length sequence)` counts the elements of a list, vector, bytes
+/// or string
+pub fn length<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "length", list, 1, Some(1)));
+    Ok(integer(try_result!(elements("length", &arguments[0])).len()))
+}
+
+/// `(member element list)` returns the tail of `list` starting at the
+/// first element `equal` to `element`, nil when there is none
+pub fn member<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "member", list, 2, Some(2)));
+    let mut tail = arguments[1].clone();
+    while tail.is_list() && !tail.is_empty() {
+        if tail.head().is_equal(&arguments[0]) {
+            return Ok(tail);
+        }
+        tail = ds::cdr(&tail);
+    }
+    Ok(Value::nil())
+}
+
+/// `(delete element sequence)` returns `sequence` without the
+/// elements `equal` to `element`. Unlike Emacs the sequence itself is
+/// left untouched, so this is the same as `remove`.
+pub fn delete<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    without(vm, "delete", list)
+}
+
+/// `(remove element sequence)` returns a copy of `sequence` without
+/// the elements `equal` to `element`
+pub fn remove<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    without(vm, "remove", list)
+}
+
+/// `(number-sequence from &optional to step)` returns the list of
+/// numbers from `from` to `to` inclusive, counting by `step`, which
+/// defaults to 1 and may be negative. Without `to` the list holds
+/// `from` only.
+pub fn number_sequence<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "number-sequence", list, 1, Some(3)));
+    let from = try_result!(number_argument("number-sequence", &arguments[0]));
+    let to = match arguments.get(1).filter(|to| !to.is_empty()) {
+        Some(to) => try_result!(number_argument("number-sequence", to)),
+        None => return Ok(Value::from_iter([from.to_value()])),
+    };
+    let step = match arguments.get(2).filter(|step| !step.is_empty()) {
+        Some(step) => try_result!(number_argument("number-sequence", step)),
+        None => Number::UnsignedInteger(1),
+    };
+    if step.is_zero() {
+        return Err(with_caller!(runtime_error(
+            "number-sequence called with a step of zero".to_string(),
+            None
+        )));
+    }
+    let past = if step.is_negative() { Ordering::Less } else { Ordering::Greater };
+    let mut numbers = Vec::<Value<'c>>::new();
+    let mut current = from;
+    while current.compare(&to).is_some_and(|ordering| ordering != past) {
+        numbers.push(current.to_value());
+        current = try_result!(current.operate("+", &step));
+    }
+    Ok(Value::from_iter(numbers))
+}
+
+/// `(seq-uniq sequence &optional testfn)` returns a list of the
+/// elements of `sequence` without those `equal` to, or passing
+/// `testfn` with, an earlier element
+pub fn seq_uniq<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), "seq-uniq", list, 1, Some(2)));
+    let items = try_result!(elements("seq-uniq", &arguments[0]));
+    let mut unique = Vec::<Value<'c>>::new();
+    match arguments.get(1).filter(|function| !function.is_empty()) {
+        Some(function) => {
+            let test = Test::new(Some(function), Value::is_equal);
+            for item in items {
+                let mut seen = false;
+                for earlier in &unique {
+                    if try_result!(test.matches(vm.clone(), earlier, &item)) {
+                        seen = true;
+                        break;
+                    }
+                }
+                if !seen {
+                    unique.push(item);
+                }
+            }
+        },
+        None => {
+            let seen = HashTable::new(HashTableTest::Equal);
+            for item in items {
+                if seen.get(&item).is_none() {
+                    seen.insert(item.clone(), Value::T);
+                    unique.push(item);
+                }
+            }
+        },
+    }
+    Ok(Value::from_iter(unique))
+}
+
+/// the elements of a list, vector, bytes or string for the builtin
+/// `name`, bytes as integers and strings as characters
+pub(crate) fn elements<'c>(name: &str, sequence: &Value<'c>) -> Result<Vec<Value<'c>>> {
+    match sequence {
+        Value::Vector(vector) => Ok(vector.to_vec()),
+        Value::Bytes(bytes) => Ok(bytes.to_vec().into_iter().map(integer).collect()),
+        Value::String(string) => Ok(string.chars().map(Value::Char).collect()),
+        value if value.is_list() || value.is_empty() => Ok(value.values()),
+        value => Err(with_caller!(runtime_error(
+            format!("{} called with non-sequence value: {}", name, value),
+            None
+        ))),
+    }
+}
+
+/// calls `function`, a symbol or lambda, with already evaluated
+/// `arguments`
+fn call<'c>(
+    mut vm: Pointer<Context<'c>>,
+    function: &Value<'c>,
+    arguments: Vec<Value<'c>>,
+) -> Result<Value<'c>> {
+    Ok(try_result!(vm.inner_mut().funcall(function.clone(), Value::from_iter(arguments))))
+}
+
+fn map<'c>(
+    vm: Pointer<Context<'c>>,
+    name: &str,
+    function: &Value<'c>,
+    sequence: &Value<'c>,
+) -> Result<Vec<Value<'c>>> {
+    let mut results = Vec::<Value<'c>>::new();
+    for item in try_result!(elements(name, sequence)) {
+        results.push(try_result!(call(vm.clone(), function, vec![item])));
+    }
+    Ok(results)
+}
+
+fn filter<'c>(
+    vm: Pointer<Context<'c>>,
+    name: &str,
+    list: Value<'c>,
+    keep: bool,
+) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm.clone(), name, list, 2, Some(2)));
+    let mut kept = Vec::<Value<'c>>::new();
+    for item in try_result!(elements(name, &arguments[1])) {
+        let passed = !try_result!(call(vm.clone(), &arguments[0], vec![item.clone()])).is_empty();
+        if passed == keep {
+            kept.push(item);
+        }
+    }
+    Ok(Value::from_iter(kept))
+}
+
+fn fold_items<'c>(
+    vm: Pointer<Context<'c>>,
+    function: &Value<'c>,
+    initial: Value<'c>,
+    items: impl Iterator<Item = Value<'c>>,
+) -> Result<Value<'c>> {
+    let mut accumulator = initial;
+    for item in items {
+        accumulator = try_result!(call(vm.clone(), function, vec![accumulator, item]));
+    }
+    Ok(accumulator)
+}
+
+/// a stable merge sort calling `predicate` once per comparison, which
+/// takes an element of the right half first only when it belongs
+/// strictly before the element of the left half
+fn merge_sort<'c>(
+    vm: Pointer<Context<'c>>,
+    mut items: Vec<Value<'c>>,
+    predicate: &Value<'c>,
+) -> Result<Vec<Value<'c>>> {
+    if items.len() < 2 {
+        return Ok(items);
+    }
+    let right = items.split_off(items.len() / 2);
+    let left = try_result!(merge_sort(vm.clone(), items, predicate));
+    let right = try_result!(merge_sort(vm.clone(), right, predicate));
+    let mut sorted = Vec::<Value<'c>>::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(lhs), Some(rhs)) = (left.peek(), right.peek()) {
+        let before = try_result!(call(vm.clone(), predicate, vec![rhs.clone(), lhs.clone()]));
+        sorted.extend(if before.is_empty() { left.next() } else { right.next() });
+    }
+    sorted.extend(left);
+    sorted.extend(right);
+    Ok(sorted)
+}
+
+/// `items` as a vector when `sequence` is one, otherwise as a list
+fn same_type<'c>(sequence: &Value<'c>, items: Vec<Value<'c>>) -> Value<'c> {
+    match sequence {
+        Value::Vector(_) => Value::vector(items),
+        _ => Value::from_iter(items),
+    }
+}
+
+fn nthcdr_of<'c>(n: i64, list: &Value<'c>) -> Value<'c> {
+    let mut tail = list.clone();
+    for _ in 0..n.max(0) {
+        if !tail.is_list() || tail.is_empty() {
+            return Value::nil();
+        }
+        tail = ds::cdr(&tail);
+    }
+    tail
+}
+
+fn without<'c>(vm: Pointer<Context<'c>>, name: &str, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, name, list, 2, Some(2)));
+    let kept = try_result!(elements(name, &arguments[1]))
+        .into_iter()
+        .filter(|item| !item.is_equal(&arguments[0]))
+        .collect();
+    Ok(same_type(&arguments[1], kept))
+}
+
+fn optional_count(name: &str, value: Option<&Value>) -> Result<i64> {
+    match value.filter(|value| !value.is_empty()) {
+        Some(value) => index_argument(name, value),
+        None => Ok(1),
+    }
+}
+
+fn number_argument(name: &str, value: &Value) -> Result<Number> {
+    match Number::from_value(value) {
+        Some(number) => Ok(number),
+        None => Err(with_caller!(runtime_error(
+            format!("{} called with non-number value: {}", name, value),
+            None
+        ))),
+    }
+}
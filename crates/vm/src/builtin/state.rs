@@ -1,14 +1,13 @@
 //BinaryHeap;
 
-use sexprs_data_structures::{car, cdr, AsSymbol, Value};
+use sexprs_data_structures::{car, cdr, AsSymbol, Quotable, Value};
 use sexprs_util::try_result;
-use unique_pointer::UniquePointer;
 
 use crate::helpers::runtime_error;
-use crate::{Context, Result, Sym};
+use crate::{Context, Pointer, Result, Sym};
 
 pub fn setq<'c>(
-    mut vm: UniquePointer<Context<'c>>,
+    mut vm: Pointer<Context<'c>>,
     list: Value<'c>,
 ) -> Result<Value<'c>> {
     // info!(202, "setq");
@@ -19,19 +18,23 @@ pub fn setq<'c>(
             None,
         ));
     }
-    let head = car(&list);
-    if !head.is_symbol() {
-        return Err(runtime_error(
-            format!("setq invoked with non-symbol: {:#?}", head),
-            None,
-        ));
+    let mut value = Value::nil();
+    let mut pairs = list.values().into_iter();
+    while let (Some(head), Some(form)) = (pairs.next(), pairs.next()) {
+        if !head.is_symbol() {
+            return Err(runtime_error(
+                format!("setq invoked with non-symbol: {:#?}", head),
+                None,
+            ));
+        }
+        value = try_result!(vm.eval(form));
+        try_result!(vm.setq(&head.as_symbol(), &Sym::Value(value.clone())));
     }
-    // dbg!(&head, &tail, &list);
-    Ok(vm.set_local(&head.as_symbol(), &Sym::Value(car(&cdr(&list))))?)
+    Ok(value)
 }
 
 pub fn defun<'c>(
-    mut vm: UniquePointer<Context<'c>>,
+    mut vm: Pointer<Context<'c>>,
     list: Value<'c>,
 ) -> Result<Value<'c>> {
     let name = car(&list).as_symbol();
@@ -41,3 +44,77 @@ pub fn defun<'c>(
     // dbg!(&name, &args, &body);
     Ok(try_result!(vm.register_function(name, args, body)))
 }
+
+pub fn defmacro<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let name = car(&list).as_symbol();
+    let args = car(&cdr(&list));
+    let body = cdr(&cdr(&list));
+    Ok(try_result!(vm.register_macro(name, args, body)))
+}
+
+pub fn macroexpand_1<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let form = try_result!(vm.inner_mut().eval(list.head()));
+    let form = Quotable::unquote(&form);
+    Ok(try_result!(vm.inner_mut().macroexpand_1(form.clone()))
+        .unwrap_or(form)
+        .quote())
+}
+
+pub fn macroexpand<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let form = try_result!(vm.inner_mut().eval(list.head()));
+    Ok(try_result!(vm.inner_mut().macroexpand(Quotable::unquote(&form))).quote())
+}
+
+pub fn macroexpand_all<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let form = try_result!(vm.inner_mut().eval(list.head()));
+    Ok(try_result!(vm.inner_mut().macroexpand_all(Quotable::unquote(&form))).quote())
+}
+
+pub fn lambda<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let args = car(&list);
+    let body = cdr(&list);
+    Ok(vm.make_closure(args, body))
+}
+
+pub fn funcall<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let list = try_result!(vm.inner_mut().eval_arguments(list));
+    Ok(try_result!(vm.inner_mut().tail_funcall(car(&list), cdr(&list))))
+}
+
+pub fn apply<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let mut args = try_result!(vm.inner_mut().eval_arguments(list)).values();
+    if args.is_empty() {
+        return Err(runtime_error("apply requires a function".to_string(), None));
+    }
+    let function = args.remove(0);
+    let last = args.pop().unwrap_or_default();
+    if !last.is_list() && !last.is_empty() {
+        return Err(runtime_error(
+            format!("last argument to apply must be a list: {}", last),
+            None,
+        ));
+    }
+    args.extend(last.values());
+    Ok(try_result!(vm.inner_mut().tail_funcall(function, Value::from_iter(args))))
+}
@@ -0,0 +1,93 @@
+use sexprs_data_structures::Value;
+use sexprs_parser::parse_source;
+use sexprs_util::{try_result, with_caller};
+
+use super::{char_argument, index_argument, string_argument};
+use crate::builtin::arguments;
+use crate::builtin::math::Number;
+use crate::{runtime_error, Context, Pointer, Result};
+
+/// `(number-to-string number)` prints `number` as the reader reads it
+pub fn number_to_string<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "number-to-string", list, 1, Some(1)));
+    match Number::from_value(&arguments[0]) {
+        Some(number) => Ok(Value::string(number)),
+        None => Err(with_caller!(runtime_error(
+            format!("number-to-string called with non-numerical value: {}", arguments[0]),
+            None
+        ))),
+    }
+}
+
+/// `(string-to-number string)` reads `string`, surrounding whitespace
+/// aside, as a number of any type the reader supports, or 0 when it
+/// is not one
+pub fn string_to_number<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "string-to-number", list, 1, Some(1)));
+    let string = try_result!(string_argument("string-to-number", &arguments[0]));
+    let number = parse_source(string.trim()).ok().as_ref().and_then(Number::from_value);
+    Ok(match number {
+        Some(number) => number.to_value(),
+        None => Value::unsigned_integer(0u32),
+    })
+}
+
+/// `(symbol-name symbol)` returns the name of `symbol` as a string
+pub fn symbol_name<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "symbol-name", list, 1, Some(1)));
+    match &arguments[0] {
+        Value::Symbol(symbol) | Value::QuotedSymbol(symbol) => Ok(Value::string(symbol)),
+        Value::Nil => Ok(Value::string("nil")),
+        Value::T => Ok(Value::string("t")),
+        value => Err(with_caller!(runtime_error(
+            format!("symbol-name called with non-symbol value: {}", value),
+            None
+        ))),
+    }
+}
+
+/// `(intern name)` returns the symbol named `name`
+pub fn intern<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "intern", list, 1, Some(1)));
+    let name = try_result!(string_argument("intern", &arguments[0]));
+    Ok(match name {
+        "nil" => Value::Nil,
+        "t" => Value::T,
+        name => Value::symbol(name),
+    })
+}
+
+/// `(char-to-string char)` returns a string of the single `char`
+pub fn char_to_string<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "char-to-string", list, 1, Some(1)));
+    let char = try_result!(char_argument("char-to-string", &arguments[0]));
+    Ok(Value::string(char))
+}
+
+/// `(string-to-list string)` returns the characters of `string`
+pub fn string_to_list<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "string-to-list", list, 1, Some(1)));
+    let string = try_result!(string_argument("string-to-list", &arguments[0]));
+    Ok(string.chars().map(Value::Char).collect())
+}
+
+/// `(char-code char)` returns the unicode code point of `char`
+pub fn char_code<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "char-code", list, 1, Some(1)));
+    let char = try_result!(char_argument("char-code", &arguments[0]));
+    Ok(Value::unsigned_integer(u32::from(char)))
+}
+
+/// `(code-char code)` returns the character of the unicode code point
+/// `code`
+pub fn code_char<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "code-char", list, 1, Some(1)));
+    let code = try_result!(index_argument("code-char", &arguments[0]));
+    match u32::try_from(code).ok().and_then(char::from_u32) {
+        Some(char) => Ok(Value::Char(char)),
+        None => Err(with_caller!(runtime_error(
+            format!("code-char called with invalid code point: {}", code),
+            None
+        ))),
+    }
+}
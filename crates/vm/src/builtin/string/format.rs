@@ -0,0 +1,95 @@
+use sexprs_data_structures::Value;
+use sexprs_util::{try_result, with_caller};
+
+use super::string_argument;
+use crate::builtin::arguments;
+use crate::builtin::math::Number;
+use crate::{runtime_error, Context, Pointer, Result};
+
+/// `(format template values...)` replaces the directives of
+/// `template` with `values` in order:
+///
+/// - `%s` any value, strings, characters and quoted forms as they
+///   are, without quotes
+/// - `%d` a number truncated to an integer
+/// - `%f` a number as a decimal with 6 digits, or `%.Nf` with N
+/// - `%%` a literal `%`
+pub fn format<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "format", list, 1, None));
+    let template = try_result!(string_argument("format", &arguments[0]));
+    let mut values = arguments[1..].iter();
+    let mut result = String::new();
+    let mut chars = template.chars();
+    while let Some(char) = chars.next() {
+        if char != '%' {
+            result.push(char);
+            continue;
+        }
+        let mut precision = None::<usize>;
+        let mut directive = chars.next();
+        if directive == Some('.') {
+            let mut digits = String::new();
+            directive = chars.next();
+            while let Some(digit) = directive.filter(char::is_ascii_digit) {
+                digits.push(digit);
+                directive = chars.next();
+            }
+            precision = digits.parse().ok();
+        }
+        let directive = match directive {
+            Some('%') if precision.is_none() => {
+                result.push('%');
+                continue;
+            },
+            Some(directive @ ('s' | 'd' | 'f')) => directive,
+            _ => {
+                return Err(with_caller!(runtime_error(
+                    format!("format called with invalid directive in: {:#?}", template),
+                    None
+                )))
+            },
+        };
+        let value = match values.next() {
+            Some(value) => value,
+            None => {
+                return Err(with_caller!(runtime_error(
+                    format!("format called with not enough arguments for: {:#?}", template),
+                    None
+                )))
+            },
+        };
+        result.push_str(&try_result!(directive_to_string(directive, precision, value)));
+    }
+    Ok(Value::string(result))
+}
+
+fn directive_to_string(directive: char, precision: Option<usize>, value: &Value) -> Result<String> {
+    if directive == 's' {
+        return Ok(match value {
+            Value::String(string) => string.to_string(),
+            Value::Char(char) => char.to_string(),
+            Value::QuotedSymbol(symbol) => symbol.symbol().to_string(),
+            Value::QuotedList(cell) => Value::List(cell.clone()).to_string(),
+            value => value.to_string(),
+        });
+    }
+    let number = match Number::from_value(value) {
+        Some(number) => number,
+        None => {
+            return Err(with_caller!(runtime_error(
+                format!("format %{} called with non-numerical value: {}", directive, value),
+                None
+            )))
+        },
+    };
+    if directive == 'f' {
+        return Ok(format!("{:.*}", precision.unwrap_or(6), number.to_f64()));
+    }
+    match try_result!(number.round("truncate")).to_integer() {
+        Some(integer) => Ok(integer.to_string()),
+        None => Err(with_caller!(runtime_error(
+            format!("format %d called with non-integer value: {}", value),
+            None
+        ))),
+    }
+}
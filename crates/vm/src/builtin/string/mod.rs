@@ -0,0 +1,67 @@
+pub mod conversion;
+pub mod format;
+pub mod operation;
+
+use num_traits::ToPrimitive;
+use sexprs_data_structures::Value;
+use sexprs_util::{try_result, with_caller};
+
+use super::math::Number;
+use crate::{runtime_error, Context, Pointer, Result};
+
+pub fn print<'c>(
+    mut vm: Pointer<Context<'c>>,
+    list: Value<'c>,
+) -> Result<Value<'c>> {
+    let list = try_result!(vm.inner_mut().eval_arguments(list));
+    println!(
+        "{}",
+        list.clone()
+            .into_iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    );
+    Ok(list)
+}
+
+/// the contents of `value` for the string builtin `name`, which only
+/// accepts strings
+pub(crate) fn string_argument<'a>(name: &str, value: &'a Value) -> Result<&'a str> {
+    match value {
+        Value::String(string) => Ok(string),
+        value => Err(with_caller!(runtime_error(
+            format!("{} called with non-string value: {}", name, value),
+            None
+        ))),
+    }
+}
+
+/// the character `value` for the string builtin `name`, which only
+/// accepts characters
+pub(crate) fn char_argument(name: &str, value: &Value) -> Result<char> {
+    match value {
+        Value::Char(char) => Ok(*char),
+        value => Err(with_caller!(runtime_error(
+            format!("{} called with non-character value: {}", name, value),
+            None
+        ))),
+    }
+}
+
+/// the integer value of `value` for the string builtin `name`
+pub(crate) fn index_argument(name: &str, value: &Value) -> Result<i64> {
+    match Number::from_value(value).and_then(|number| number.to_integer()) {
+        Some(integer) => match integer.to_i64() {
+            Some(index) => Ok(index),
+            None => Err(with_caller!(runtime_error(
+                format!("{} called with out of range index: {}", name, integer),
+                None
+            ))),
+        },
+        None => Err(with_caller!(runtime_error(
+            format!("{} called with non-integer value: {}", name, value),
+            None
+        ))),
+    }
+}
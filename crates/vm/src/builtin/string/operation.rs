@@ -0,0 +1,185 @@
+use sexprs_data_structures::{BigInt, Value};
+use sexprs_util::{try_result, with_caller};
+
+use super::{index_argument, string_argument};
+use crate::builtin::arguments;
+use crate::builtin::math::Number;
+use crate::{runtime_error, Context, Pointer, Result};
+
+/// `(concat strings...)` joins strings into a new one, nil counts as
+/// the empty string
+pub fn concat<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let mut result = String::new();
+    for value in try_result!(arguments(vm, "concat", list, 0, None)) {
+        if !value.is_nil() {
+            result.push_str(try_result!(string_argument("concat", &value)));
+        }
+    }
+    Ok(Value::string(result))
+}
+
+/// `(substring string from &optional to)` returns the characters of
+/// `string` from index `from` up to, not including, `to`. Negative
+/// indices count from the end of `string`
+pub fn substring<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "substring", list, 2, Some(3)));
+    let string = try_result!(string_argument("substring", &arguments[0]));
+    let length = string.chars().count();
+    let from = try_result!(index_argument("substring", &arguments[1]));
+    let to = match arguments.get(2).filter(|to| !to.is_nil()) {
+        Some(to) => try_result!(index_argument("substring", to)),
+        None => length as i64,
+    };
+    match (position(from, length), position(to, length)) {
+        (Some(from), Some(to)) if from <= to =>
+            Ok(Value::string(string.chars().skip(from).take(to - from).collect::<String>())),
+        _ => Err(with_caller!(runtime_error(
+            format!("substring called with out of range indices: {} {} for {:#?}", from, to, string),
+            None
+        ))),
+    }
+}
+
+/// `(string-length string)` counts the characters of `string`
+pub fn string_length<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "string-length", list, 1, Some(1)));
+    let string = try_result!(string_argument("string-length", &arguments[0]));
+    Ok(count(string.chars().count()))
+}
+
+pub fn string_upcase<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    map_string(vm, "string-upcase", list, str::to_uppercase)
+}
+
+pub fn string_downcase<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    map_string(vm, "string-downcase", list, str::to_lowercase)
+}
+
+/// `(string-trim string)` removes leading and trailing whitespace
+pub fn string_trim<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    map_string(vm, "string-trim", list, |string| string.trim().to_string())
+}
+
+/// `(split-string string &optional separator)` splits `string` at
+/// each occurrence of `separator`, or at runs of whitespace without
+/// empty strings when no separator is given
+pub fn split_string<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "split-string", list, 1, Some(2)));
+    let string = try_result!(string_argument("split-string", &arguments[0]));
+    Ok(match arguments.get(1).filter(|separator| !separator.is_nil()) {
+        Some(separator) => {
+            let separator = try_result!(string_argument("split-string", separator));
+            if separator.is_empty() {
+                return Err(with_caller!(runtime_error(
+                    "split-string called with empty separator".to_string(),
+                    None
+                )));
+            }
+            string.split(separator).map(Value::string).collect()
+        },
+        None => string.split_whitespace().map(Value::string).collect(),
+    })
+}
+
+/// `(string-join strings &optional separator)` concatenates the list
+/// `strings` with `separator` in between
+pub fn string_join<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "string-join", list, 1, Some(2)));
+    let separator = match arguments.get(1).filter(|separator| !separator.is_nil()) {
+        Some(separator) => try_result!(string_argument("string-join", separator)),
+        None => "",
+    };
+    let values = arguments[0].values();
+    let mut strings = Vec::<&str>::new();
+    for value in &values {
+        strings.push(try_result!(string_argument("string-join", value)));
+    }
+    Ok(Value::string(strings.join(separator)))
+}
+
+/// `(string-prefix-p prefix string)` is t when `string` starts with
+/// `prefix`
+pub fn string_prefix_p<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "string-prefix-p", list, 2, Some(2)));
+    let prefix = try_result!(string_argument("string-prefix-p", &arguments[0]));
+    let string = try_result!(string_argument("string-prefix-p", &arguments[1]));
+    Ok(string.starts_with(prefix).into())
+}
+
+/// `(string-suffix-p suffix string)` is t when `string` ends with
+/// `suffix`
+pub fn string_suffix_p<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "string-suffix-p", list, 2, Some(2)));
+    let suffix = try_result!(string_argument("string-suffix-p", &arguments[0]));
+    let string = try_result!(string_argument("string-suffix-p", &arguments[1]));
+    Ok(string.ends_with(suffix).into())
+}
+
+/// `(string-search needle haystack &optional start)` returns the
+/// index of the first occurrence of `needle` in `haystack` at or
+/// after `start`, nil when there is none
+pub fn string_search<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "string-search", list, 2, Some(3)));
+    let needle = try_result!(string_argument("string-search", &arguments[0]));
+    let haystack = try_result!(string_argument("string-search", &arguments[1]));
+    let length = haystack.chars().count();
+    let start = match arguments.get(2).filter(|start| !start.is_nil()) {
+        Some(start) => {
+            let index = try_result!(index_argument("string-search", start));
+            match usize::try_from(index).ok().filter(|start| *start <= length) {
+                Some(start) => start,
+                None => {
+                    return Err(with_caller!(runtime_error(
+                        format!("string-search called with out of range index: {}", index),
+                        None
+                    )))
+                },
+            }
+        },
+        None => 0,
+    };
+    let offset = haystack.char_indices().nth(start).map(|(offset, _)| offset).unwrap_or(haystack.len());
+    Ok(match haystack[offset..].find(needle) {
+        Some(found) => count(start + haystack[offset..offset + found].chars().count()),
+        None => Value::Nil,
+    })
+}
+
+/// `(string-replace from to string)` replaces every occurrence of
+/// `from` in `string` with `to`
+pub fn string_replace<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "string-replace", list, 3, Some(3)));
+    let from = try_result!(string_argument("string-replace", &arguments[0]));
+    let to = try_result!(string_argument("string-replace", &arguments[1]));
+    let string = try_result!(string_argument("string-replace", &arguments[2]));
+    if from.is_empty() {
+        return Err(with_caller!(runtime_error(
+            "string-replace called with empty string to replace".to_string(),
+            None
+        )));
+    }
+    Ok(Value::string(string.replace(from, to)))
+}
+
+fn map_string<'c>(
+    vm: Pointer<Context<'c>>,
+    name: &str,
+    list: Value<'c>,
+    map: fn(&str) -> String,
+) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, name, list, 1, Some(1)));
+    let string = try_result!(string_argument(name, &arguments[0]));
+    Ok(Value::string(map(string)))
+}
+
+/// `index` of a string of `length` characters, counting from its end
+/// when negative
+fn position(index: i64, length: usize) -> Option<usize> {
+    let index = if index < 0 { length as i64 + index } else { index };
+    usize::try_from(index).ok().filter(|index| *index <= length)
+}
+
+/// `count` as the smallest integer type holding it
+fn count<'c>(count: usize) -> Value<'c> {
+    Number::from_integer(BigInt::from(count), 0).to_value()
+}
@@ -2,13 +2,12 @@
 
 use sexprs_data_structures::{car, append, cdr, AsSymbol, Value};
 use sexprs_util::try_result;
-use unique_pointer::UniquePointer;
 
 use crate::helpers::runtime_error;
-use crate::{Context, Result, Sym};
+use crate::{Context, Pointer, Result, Sym};
 
 pub fn listp<'c>(
-    mut vm: UniquePointer<Context<'c>>,
+    mut vm: Pointer<Context<'c>>,
     list: Value<'c>,
 ) -> Result<Value<'c>> {
     let value = try_result!(vm.eval(car(&list)));
@@ -16,7 +15,7 @@ pub fn listp<'c>(
 }
 
 pub fn null<'c>(
-    mut vm: UniquePointer<Context<'c>>,
+    mut vm: Pointer<Context<'c>>,
     list: Value<'c>,
 ) -> Result<Value<'c>> {
     let value = try_result!(vm.eval(car(&list)));
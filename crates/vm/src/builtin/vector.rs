@@ -0,0 +1,149 @@
+use sexprs_data_structures::{BigInt, Value};
+use sexprs_util::{try_result, with_caller};
+
+use super::arguments;
+use super::sequence::elements;
+use super::math::Number;
+use super::string::index_argument;
+use crate::{runtime_error, Context, Pointer, Result};
+
+/// `(make-vector length init)` returns a vector of `length` elements
+/// all `init`
+pub fn make_vector<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "make-vector", list, 2, Some(2)));
+    let length = try_result!(index_argument("make-vector", &arguments[0]));
+    match usize::try_from(length) {
+        Ok(length) => Ok(Value::vector(vec![arguments[1].clone(); length])),
+        Err(_) => Err(with_caller!(runtime_error(
+            format!("make-vector called with negative length: {}", length),
+            None
+        ))),
+    }
+}
+
+/// `(vector objects...)` returns a vector of `objects`
+pub fn vector<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    Ok(Value::vector(try_result!(arguments(vm, "vector", list, 0, None))))
+}
+
+/// `(aref array index)` returns the element at `index` of a vector,
+/// bytes (as an integer) or string (as a character)
+pub fn aref<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "aref", list, 2, Some(2)));
+    let index = try_result!(index_argument("aref", &arguments[1]));
+    let item = usize::try_from(index).ok().and_then(|index| match &arguments[0] {
+        Value::Vector(vector) => vector.get(index),
+        Value::Bytes(bytes) => bytes.get(index).map(integer),
+        Value::String(string) => string.chars().nth(index).map(Value::Char),
+        _ => None,
+    });
+    match item {
+        Some(item) => Ok(item),
+        None => Err(try_result!(out_of_range("aref", &arguments[0], index))),
+    }
+}
+
+/// `(aset array index value)` replaces the element at `index` of a
+/// vector or bytes with `value`, which must be a byte for bytes, and
+/// returns `value`
+pub fn aset<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "aset", list, 3, Some(3)));
+    let index = try_result!(index_argument("aset", &arguments[1]));
+    let value = arguments[2].clone();
+    let set = match (&arguments[0], usize::try_from(index)) {
+        (Value::Vector(vector), Ok(index)) => vector.set(index, value.clone()),
+        (Value::Bytes(bytes), Ok(index)) => {
+            let byte = try_result!(byte_argument("aset", &value));
+            bytes.set(index, byte)
+        },
+        _ => false,
+    };
+    if set {
+        Ok(value)
+    } else {
+        Err(try_result!(out_of_range("aset", &arguments[0], index)))
+    }
+}
+
+/// `(vector-length array)` counts the elements of a vector or bytes
+pub fn vector_length<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "vector-length", list, 1, Some(1)));
+    let length = match &arguments[0] {
+        Value::Vector(vector) => vector.len(),
+        Value::Bytes(bytes) => bytes.len(),
+        value => return Err(try_result!(not_an_array("vector-length", value))),
+    };
+    Ok(integer(length))
+}
+
+/// `(vconcat sequences...)` returns a vector of the elements of
+/// vectors, bytes, strings and lists in order
+pub fn vconcat<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let mut items = Vec::<Value<'c>>::new();
+    for sequence in try_result!(arguments(vm, "vconcat", list, 0, None)) {
+        items.extend(try_result!(elements("vconcat", &sequence)));
+    }
+    Ok(Value::vector(items))
+}
+
+/// `(vector-to-list array)` returns the elements of a vector or bytes
+/// as a list
+pub fn vector_to_list<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "vector-to-list", list, 1, Some(1)));
+    match &arguments[0] {
+        Value::Vector(_) | Value::Bytes(_) =>
+            Ok(try_result!(elements("vector-to-list", &arguments[0])).into_iter().collect()),
+        value => Err(try_result!(not_an_array("vector-to-list", value))),
+    }
+}
+
+/// `(list-to-vector list)` returns a vector of the elements of `list`
+pub fn list_to_vector<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "list-to-vector", list, 1, Some(1)));
+    Ok(Value::vector(arguments[0].values()))
+}
+
+/// `(list-to-bytes list)` returns bytes of the integers of `list`,
+/// each between 0 and 255
+pub fn list_to_bytes<'c>(vm: Pointer<Context<'c>>, list: Value<'c>) -> Result<Value<'c>> {
+    let arguments = try_result!(arguments(vm, "list-to-bytes", list, 1, Some(1)));
+    let mut bytes = Vec::<u8>::new();
+    for value in arguments[0].values() {
+        bytes.push(try_result!(byte_argument("list-to-bytes", &value)));
+    }
+    Ok(Value::bytes(bytes))
+}
+
+pub(crate) fn integer<'c, T: Into<BigInt>>(integer: T) -> Value<'c> {
+    Number::from_integer(integer.into(), 0).to_value()
+}
+
+fn byte_argument(name: &str, value: &Value) -> Result<u8> {
+    match Number::from_value(value)
+        .and_then(|number| number.to_integer())
+        .and_then(|integer| u8::try_from(integer).ok())
+    {
+        Some(byte) => Ok(byte),
+        None => Err(with_caller!(runtime_error(
+            format!("{} called with non-byte value: {}", name, value),
+            None
+        ))),
+    }
+}
+
+fn out_of_range(name: &str, array: &Value, index: i64) -> Result<crate::Error> {
+    match array {
+        Value::Vector(_) | Value::Bytes(_) | Value::String(_) => Ok(with_caller!(runtime_error(
+            format!("{} called with out of range index: {} for {}", name, index, array),
+            None
+        ))),
+        value => not_an_array(name, value),
+    }
+}
+
+fn not_an_array(name: &str, value: &Value) -> Result<crate::Error> {
+    Ok(with_caller!(runtime_error(
+        format!("{} called with non-array value: {}", name, value),
+        None
+    )))
+}
@@ -0,0 +1,98 @@
+use std::fmt::{Debug, Display, Formatter};
+
+use sexprs_data_structures::{Symbol, Value};
+use sexprs_util::{with_caller, Traceback};
+
+use crate::{Error, ErrorType};
+
+/// a lisp level error: the symbol naming its type and the data it
+/// was signalled with, as in `(signal 'my-error '(1 2))`
+#[derive(Clone, PartialEq)]
+pub struct Condition<'c> {
+    pub symbol: Symbol<'c>,
+    pub data: Value<'c>,
+}
+
+impl<'c> Condition<'c> {
+    pub fn new(symbol: Symbol<'c>, data: Value<'c>) -> Condition<'c> {
+        Condition { symbol, data }
+    }
+
+    /// the condition of a native error: the symbol of its
+    /// [`ErrorType`] and its message as data
+    pub fn from_error(error: &Error) -> Condition<'c> {
+        Condition::new(
+            Symbol::new(error.ty().condition_symbol()),
+            Value::from_iter([Value::string(error.message())]),
+        )
+    }
+
+    pub fn error_type(&self) -> ErrorType {
+        ErrorType::from_condition_symbol(self.symbol.symbol())
+    }
+
+    /// the message of the error the condition unwinds with: the
+    /// string of an `(error "message")`, otherwise the symbol and data
+    pub fn message(&self) -> String {
+        match self.data.values().as_slice() {
+            [Value::String(message)] => message.to_string(),
+            _ if self.data.is_empty() => self.symbol.to_string(),
+            _ => format!("{}: {}", self.symbol, self.data),
+        }
+    }
+
+    pub fn to_error(&self) -> Error {
+        with_caller!(Error::new(self.message(), self.error_type()))
+    }
+
+    /// `(symbol . data)` as bound by `condition-case`
+    pub fn as_value(&self) -> Value<'c> {
+        let mut values = vec![Value::symbol(&self.symbol)];
+        values.extend(self.data.values());
+        Value::from_iter(values)
+    }
+}
+
+impl<'c> Display for Condition<'c> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_value())
+    }
+}
+
+impl<'c> Debug for Condition<'c> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "Condition {{ symbol: {}, data: {} }}", self.symbol, self.data)
+    }
+}
+
+impl ErrorType {
+    /// the lisp condition symbol errors of this type are signalled as
+    pub fn condition_symbol(&self) -> &'static str {
+        match self {
+            ErrorType::IOError => "file-error",
+            ErrorType::FormatError => "format-error",
+            ErrorType::ParserError => "invalid-read-syntax",
+            ErrorType::RuntimeError | ErrorType::Signal => "error",
+            ErrorType::UnboundVariable => "void-variable",
+            ErrorType::UnboundFunction => "void-function",
+            ErrorType::NoCatch => "no-catch",
+            ErrorType::ArithmeticError => "arith-error",
+        }
+    }
+
+    /// the error type of a condition symbol, [`ErrorType::Signal`] for
+    /// symbols defined by lisp code
+    pub fn from_condition_symbol(symbol: &str) -> ErrorType {
+        match symbol {
+            "file-error" => ErrorType::IOError,
+            "format-error" => ErrorType::FormatError,
+            "invalid-read-syntax" => ErrorType::ParserError,
+            "error" => ErrorType::RuntimeError,
+            "void-variable" => ErrorType::UnboundVariable,
+            "void-function" => ErrorType::UnboundFunction,
+            "no-catch" => ErrorType::NoCatch,
+            "arith-error" => ErrorType::ArithmeticError,
+            _ => ErrorType::Signal,
+        }
+    }
+}
@@ -1,19 +1,48 @@
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 
 use sexprs_data_structures::{
-    append, car, cdr, AsSymbol, Cell, Quotable, Symbol, Value,
+    car, cdr, AsSymbol, Cell, Procedure, Quotable, Symbol, Value,
 };
-use sexprs_parser::parse_source;
-use sexprs_util::{admonition, try_result};
-use unique_pointer::UniquePointer;
+use sexprs_parser::{parse_program_with_locations, Location};
+use sexprs_util::{admonition, try_result, with_caller, Traceback};
 
-use crate::{Function, Result, Sym, SymbolTable, VirtualMachine};
+use crate::helpers::unbound_function;
+use crate::{
+    runtime_error, Backtrace, Condition, Environment, Error, ErrorType, Function, Pointer,
+    Result, StackFrame, Sym, SymbolTable, VirtualMachine,
+};
 
 #[allow(unused)]
 #[derive(Clone)]
 pub struct Context<'c> {
     pub(crate) symbols: SymbolTable<'c>,
-    pub(crate) vm: UniquePointer<VirtualMachine<'c>>,
+    pub(crate) vm: Pointer<VirtualMachine<'c>>,
+    /// the innermost form being evaluated that was read from source,
+    /// which locates errors and calls
+    pub(crate) form: Option<Value<'c>>,
+    /// set right before evaluating a form in tail position
+    pub(crate) tail: bool,
+    /// whether the builtin being called was itself called in tail
+    /// position, see [`Context::eval_tail`]
+    pub(crate) tail_form: bool,
+    /// call deferred by a tail position call, run by the trampoline
+    /// in [`Function::apply`]
+    pub(crate) tail_call: Option<(Function<'c>, Value<'c>, Option<Value<'c>>)>,
+    /// expression of the lisp function call being made, taken by
+    /// [`Function::call`] for its frame on the lisp call stack
+    pub(crate) call_form: Option<Value<'c>>,
+    /// the lisp call stack, outermost call first
+    pub(crate) frames: Vec<StackFrame<'c>>,
+    /// the lisp call stack when the error being unwound was raised,
+    /// innermost call first
+    pub(crate) backtrace: Option<Vec<StackFrame<'c>>>,
+    /// conditions being signalled by id, see [`Context::signal`]
+    pub(crate) conditions: BTreeMap<usize, Condition<'c>>,
+    /// id of the last condition signalled
+    pub(crate) last_condition: usize,
+    /// tag and value being thrown, see [`Context::throw`]
+    pub(crate) thrown: Option<(Value<'c>, Value<'c>)>,
 }
 
 impl<'c> Debug for Context<'c> {
@@ -30,15 +59,28 @@ impl<'c> Debug for Context<'c> {
 
 impl<'c> Context<'c> {
     pub fn new(
-        vm: UniquePointer<VirtualMachine<'c>>,
+        vm: Pointer<VirtualMachine<'c>>,
         symbols: SymbolTable<'c>,
     ) -> Context<'c> {
-        let context = Context { symbols, vm };
+        let context = Context {
+            symbols,
+            vm,
+            form: None,
+            tail: false,
+            tail_form: false,
+            tail_call: None,
+            call_form: None,
+            frames: Vec::new(),
+            backtrace: None,
+            conditions: BTreeMap::new(),
+            last_condition: 0,
+            thrown: None,
+        };
         context
     }
 
-    pub fn vm(&self) -> UniquePointer<Context<'c>> {
-        UniquePointer::read_only(self)
+    pub fn vm(&self) -> Pointer<Context<'c>> {
+        Pointer::read_only(self)
     }
 
     pub fn register_function(
@@ -59,33 +101,178 @@ impl<'c> Context<'c> {
         Ok(function.as_value())
     }
 
-    pub fn symbol_is_function<T: AsSymbol<'c>>(&mut self, sym: T) -> Result<bool> {
-        if !sym.is_symbol() {
-            return Ok(false);
-        }
-        let symbol = &sym.as_symbol();
-        let sym = try_result!(self
+    pub fn register_macro(
+        &mut self,
+        name: Symbol<'c>,
+        args: Value<'c>,
+        body: Value<'c>,
+    ) -> Result<Value<'c>> {
+        let function = Sym::<'c>::Function(Function::Macro {
+            name: name.clone(),
+            args: args.clone(),
+            body: body.clone(),
+        });
+        try_result!(self
             .symbols
-            .get(UniquePointer::read_only(self), &symbol));
-        match sym {
-            Sym::Value(_) => Ok(false),
-            Sym::Function(_) => Ok(true),
+            .set_global(self.vm(), &name, &function.clone(),));
+
+        Ok(function.as_value())
+    }
+
+    /// an anonymous function capturing the current environment, as
+    /// made by `lambda`
+    pub fn make_closure(&self, args: Value<'c>, body: Value<'c>) -> Value<'c> {
+        let function = self.closure(
+            &Symbol::new("lambda"),
+            args,
+            body,
+            self.symbols.environment(),
+        );
+        Value::Procedure(Procedure::new(function))
+    }
+
+    pub fn closure(
+        &self,
+        name: &Symbol<'c>,
+        args: Value<'c>,
+        body: Value<'c>,
+        env: Environment<'c>,
+    ) -> Function<'c> {
+        Function::Closure {
+            name: name.clone(),
+            args,
+            body,
+            env: if env.is_global() { None } else { Some(env) },
+        }
+    }
+
+    /// evaluates each form of `body` in order and returns the value
+    /// of the last one
+    pub fn eval_body(&mut self, body: Value<'c>) -> Result<Value<'c>> {
+        let mut value = Value::nil();
+        for form in body.values() {
+            value = try_result!(self.eval(form));
+        }
+        Ok(value)
+    }
+
+    /// like [`Context::eval_body`] but evaluates the last form with
+    /// [`Context::eval_tail`]
+    pub fn eval_tail_body(&mut self, body: Value<'c>) -> Result<Value<'c>> {
+        let mut forms = body.values();
+        let last = forms.pop().unwrap_or_default();
+        for form in forms {
+            try_result!(self.eval(form));
+        }
+        self.eval_tail(last)
+    }
+
+    /// like [`Context::eval_tail_body`] but within `environment`,
+    /// restoring the current environment afterwards
+    pub fn eval_body_within(
+        &mut self,
+        environment: Environment<'c>,
+        body: Value<'c>,
+    ) -> Result<Value<'c>> {
+        let previous = self.symbols.enter(environment);
+        let value = self.eval_tail_body(body);
+        self.symbols.leave(previous);
+        value
+    }
+
+    /// resolves `function`, a symbol or evaluated lambda, and calls
+    /// it with the already evaluated `args`
+    pub fn funcall(&mut self, function: Value<'c>, args: Value<'c>) -> Result<Value<'c>> {
+        let function = try_result!(self.resolve_function(&function));
+        Ok(try_result!(function.apply(Pointer::read_only(self), args)))
+    }
+
+    /// like [`Context::funcall`] but, when the builtin calling it was
+    /// itself called in tail position, defers a call to a lisp
+    /// function to the trampoline of the caller, as `funcall` and
+    /// `apply` do
+    pub fn tail_funcall(&mut self, function: Value<'c>, args: Value<'c>) -> Result<Value<'c>> {
+        let function = try_result!(self.resolve_function(&function));
+        if self.tail_form && (function.is_defun() || function.is_closure()) {
+            self.tail_call = Some((function, args, None));
+            return Ok(Value::nil());
+        }
+        Ok(try_result!(function.apply(Pointer::read_only(self), args)))
+    }
+
+    /// the function named by the symbol `function` or held by the
+    /// procedure `function`
+    pub fn resolve_function(&mut self, function: &Value<'c>) -> Result<Function<'c>> {
+        match function {
+            Value::Symbol(_) | Value::QuotedSymbol(_) => {
+                let name = function.as_symbol().unquote();
+                match try_result!(self.get_symbol_function(&name)) {
+                    Some(function) => Ok(function),
+                    None => Err(unbound_function(&name, self.locate(&name))),
+                }
+            },
+            Value::Procedure(procedure) => match procedure.downcast_ref::<Function<'c>>() {
+                Some(function) => Ok(function.clone()),
+                None => Err(runtime_error(format!("invalid function: {}", function), None)),
+            },
+            _ => Err(runtime_error(format!("invalid function: {}", function), None)),
+        }
+    }
+
+    /// expands `form` once if it is a macro call, returns `None`
+    /// otherwise
+    pub fn macroexpand_1(&mut self, form: Value<'c>) -> Result<Option<Value<'c>>> {
+        if !matches!(form, Value::List(_)) {
+            return Ok(None);
+        }
+        let head = car(&form);
+        if !head.is_symbol() || head.is_quoted() {
+            return Ok(None);
+        }
+        match try_result!(self.get_symbol_function(&head)) {
+            Some(function) if function.is_macro() => Ok(Some(try_result!(
+                function.expand(Pointer::read_only(self), cdr(&form))
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    /// expands `form` repeatedly until it is no longer a macro call
+    pub fn macroexpand(&mut self, mut form: Value<'c>) -> Result<Value<'c>> {
+        while let Some(expansion) = try_result!(self.macroexpand_1(form.clone())) {
+            form = expansion;
+        }
+        Ok(form)
+    }
+
+    /// expands `form` and every unquoted subform that is a macro call
+    pub fn macroexpand_all(&mut self, form: Value<'c>) -> Result<Value<'c>> {
+        let form = try_result!(self.macroexpand(form));
+        if !matches!(form, Value::List(_)) || car(&form).to_string() == "quote" {
+            return Ok(form);
         }
+        let mut items = Vec::<Value<'c>>::new();
+        for item in form.values() {
+            items.push(try_result!(self.macroexpand_all(item)));
+        }
+        Ok(Value::from_iter(items))
+    }
+
+    pub fn symbol_is_function<T: AsSymbol<'c>>(&mut self, sym: T) -> Result<bool> {
+        Ok(try_result!(self.get_symbol_function(sym)).is_some())
     }
 
     pub fn get_symbol_function<T: AsSymbol<'c>>(
         &mut self,
         sym: T,
     ) -> Result<Option<Function<'c>>> {
-        let sym = sym.as_symbol();
-        let symbol = try_result!(self
-            .symbols
-            .get(UniquePointer::read_only(self), &sym));
-        let result = match symbol {
-            Sym::Value(_) => Ok(None),
-            Sym::Function(function) => Ok(Some(function)),
-        };
-        result
+        if !sym.is_symbol() {
+            return Ok(None);
+        }
+        match self.symbols.lookup(&sym.as_symbol()) {
+            Some(Sym::Function(function)) => Ok(Some(function)),
+            _ => Ok(None),
+        }
     }
 
     pub fn eval_symbol_function<T: AsSymbol<'c>>(
@@ -94,13 +281,15 @@ impl<'c> Context<'c> {
         list: Value<'c>,
     ) -> Result<Value<'c>> {
         let sym = sym.as_symbol();
-        let vm = UniquePointer::read_only(self);
+        let vm = Pointer::read_only(self);
 
         match try_result!(self.get_symbol_function(&sym)) {
             Some(function) => {
                 let result = try_result!(function.call(vm, list));
                 Ok(result)
             },
+            None if !self.symbols.self_evaluating_symbols =>
+                Err(unbound_function(&sym, self.locate(&sym))),
             None => Ok(Value::from({
                 let mut cell = Cell::nil();
                 cell.push_value(Value::from(sym));
@@ -112,35 +301,231 @@ impl<'c> Context<'c> {
         }
     }
 
-    pub fn eval_string(&mut self, string: &'c str) -> Result<Value<'c>> {
-        Ok(try_result!(self.eval(try_result!(parse_source(string)))))
+    /// records `condition` so that `condition-case` handlers receive
+    /// its data and returns the error to unwind with, which carries
+    /// the id of the condition
+    pub fn signal(&mut self, condition: Condition<'c>) -> Error {
+        self.last_condition += 1;
+        let error = condition.to_error().with_condition(self.last_condition);
+        self.conditions.insert(self.last_condition, condition);
+        error
+    }
+
+    /// the condition `error` was signalled with, or the condition of
+    /// a native error
+    pub fn condition_of(&self, error: &Error) -> Condition<'c> {
+        match error.condition().and_then(|id| self.conditions.get(&id)) {
+            Some(condition) => condition.clone(),
+            None => Condition::from_error(error),
+        }
+    }
+
+    /// like [`Context::condition_of`] but forgets the condition, for
+    /// handlers that stop the unwinding
+    pub fn take_condition(&mut self, error: &Error) -> Condition<'c> {
+        let condition = self.condition_of(error);
+        if let Some(id) = error.condition() {
+            self.conditions.remove(&id);
+        }
+        self.backtrace = None;
+        condition
+    }
+
+    /// the lisp call stack at the point the error being unwound, or
+    /// last returned by [`Context::eval_program`], was raised
+    pub fn backtrace(&self) -> Backtrace<'c> {
+        Backtrace::new(self.backtrace.clone().unwrap_or_default())
+    }
+
+    /// records `value` as thrown to `tag` and returns the error to
+    /// unwind with until the matching `catch`
+    pub fn throw(&mut self, tag: Value<'c>, value: Value<'c>) -> Error {
+        let error = with_caller!(Error::new(
+            format!("no catch for tag: {}", tag),
+            ErrorType::NoCatch
+        ));
+        self.thrown = Some((tag, value));
+        error
+    }
+
+    pub fn eval_string(&mut self, string: &str) -> Result<Value<'c>> {
+        self.eval_program(string)
+    }
+
+    pub fn eval_program(&mut self, string: &str) -> Result<Value<'c>> {
+        self.eval_source(string, None)
+    }
+
+    /// like [`Context::eval_program`] but locates errors and
+    /// backtraces within `filename`
+    pub fn eval_source(&mut self, string: &str, filename: Option<&str>) -> Result<Value<'c>> {
+        let (forms, locations) = try_result!(parse_program_with_locations(string, filename));
+        self.symbols.add_locations(locations);
+        let count = forms.len();
+        let mut value = Value::nil();
+        self.conditions.clear();
+        self.thrown = None;
+        self.frames.clear();
+        self.backtrace = None;
+        for (index, form) in forms.into_iter().enumerate() {
+            value = match self.eval(form.clone()) {
+                Ok(value) => value,
+                Err(error) => {
+                    return Err(with_caller!(Error::with_previous_error(
+                        format!(
+                            "form {} of {} failed: {}: {}",
+                            index + 1,
+                            count,
+                            form,
+                            error.message()
+                        ),
+                        error.ty(),
+                        Some(error),
+                    )))
+                },
+            };
+        }
+        Ok(value)
     }
 
     pub fn eval(&mut self, list: Value<'c>) -> Result<Value<'c>> {
+        if !self.symbols.is_located(&list) {
+            return self.eval_form(list);
+        }
+        let outer = self.form.replace(list.clone());
+        let value = self.eval_form(list);
+        self.form = outer;
+        value
+    }
+
+    /// a frame of the lisp call stack for a call to `name` made by
+    /// `form`, located where `form` was read
+    pub(crate) fn frame(&self, name: Symbol<'c>, args: Value<'c>, form: Option<Value<'c>>) -> StackFrame<'c> {
+        let location = form.as_ref().and_then(|form| self.symbols.location(form));
+        StackFrame::new(name, args, form, location)
+    }
+
+    /// the location of the innermost form being evaluated that was
+    /// read from source
+    pub fn location(&self) -> Option<Location> {
+        self.symbols.location(self.form.as_ref()?)
+    }
+
+    /// the location of `sym` within the innermost form being
+    /// evaluated, or of the form itself when `sym` is not one of its
+    /// items
+    pub fn locate(&self, sym: &Symbol<'c>) -> Option<Location> {
+        let location = self.location()?;
+        let index = self.form.as_ref()?.values().iter().position(|item| match item {
+            Value::Symbol(symbol) => symbol == sym,
+            _ => false,
+        });
+        match index.and_then(|index| location.item(index)) {
+            Some(item) => Some(item),
+            None => Some(location),
+        }
+    }
+
+    fn eval_form(&mut self, list: Value<'c>) -> Result<Value<'c>> {
+        let tail = std::mem::take(&mut self.tail);
         if list.is_quoted() || list.is_nil() {
             return Ok(list);
         }
+        match &list {
+            Value::Symbol(sym) => {
+                return Ok(try_result!(self
+                    .symbols
+                    .get(Pointer::read_only(self), sym))
+                .as_value())
+            },
+            Value::List(_) => {},
+            _ => return Ok(list),
+        }
         let head = car(&list);
-        let value = if try_result!(self.symbol_is_function(&head)) {
-            try_result!(self.eval_symbol_function(head, cdr(&list)))
+        if matches!(head, Value::List(_)) && car(&head).to_string() == "lambda" {
+            let function = try_result!(self.eval(head));
+            let args = try_result!(self.eval_arguments(cdr(&list)));
+            return self.funcall(function, args);
+        }
+        let value = if let Some(function) = try_result!(self.get_symbol_function(&head)) {
+            if function.is_macro() && !head.is_quoted() {
+                let expansion =
+                    try_result!(function.expand(Pointer::read_only(self), cdr(&list)));
+                self.tail = tail;
+                return self.eval(expansion);
+            }
+            if tail && (function.is_defun() || function.is_closure()) {
+                let args = try_result!(self.eval_arguments(cdr(&list)));
+                self.tail_call = Some((function, args, Some(list)));
+                return Ok(Value::nil());
+            }
+            if function.is_defun() || function.is_closure() {
+                self.call_form = Some(list.clone());
+            }
+            let outer = std::mem::replace(&mut self.tail_form, tail);
+            let value = function.call(Pointer::read_only(self), cdr(&list));
+            self.tail_form = outer;
+            try_result!(value)
+        } else if matches!(head, Value::Symbol(_))
+            && !self.symbols.self_evaluating_symbols
+            && self.symbols.lookup(&head.as_symbol()).is_none()
+        {
+            return Err(unbound_function(&head.as_symbol(), self.locate(&head.as_symbol())));
         } else {
             try_result!(self.eval_list(list.clone()))
         };
         Ok(value)
     }
 
+    /// evaluates `form` in tail position when the builtin calling it
+    /// was itself evaluated in tail position. Builtins use it for the
+    /// form whose value they return as is, such as the branches of `if`.
+    pub fn eval_tail(&mut self, form: Value<'c>) -> Result<Value<'c>> {
+        self.tail = self.tail_form;
+        self.eval(form)
+    }
+
+    /// evaluates the last form of a function body, deferring a call to
+    /// another lisp function to the caller's trampoline
+    pub(crate) fn eval_in_tail_position(&mut self, form: Value<'c>) -> Result<Value<'c>> {
+        self.tail = true;
+        let value = self.eval(form);
+        if value.is_err() {
+            self.tail_call = None;
+        }
+        value
+    }
+
+    /// evaluates each item of `list` in order and returns a list
+    /// of their values
+    pub fn eval_arguments(&mut self, list: Value<'c>) -> Result<Value<'c>> {
+        let mut values = Vec::<Value<'c>>::new();
+        for value in list.values() {
+            values.push(try_result!(self.eval(value)));
+        }
+        Ok(Value::from_iter(values))
+    }
+
     pub fn eval_list(&mut self, list: Value<'c>) -> Result<Value<'c>> {
         let mut cell = Cell::nil();
         for value in list.into_iter() {
-            cell.push_value(match value {
+            let value = match value {
                 Value::Symbol(ref sym) => try_result!(self
                     .symbols
-                    .get(UniquePointer::read_only(self), &sym))
+                    .get(Pointer::read_only(self), &sym))
                 .as_value(),
                 _ => value,
-            });
+            };
+            match &value {
+                Value::List(items) | Value::QuotedList(items) =>
+                    for item in items.clone().into_iter() {
+                        cell.push_value(item);
+                    },
+                Value::EmptyList | Value::EmptyQuotedList | Value::Nil => {},
+                _ => cell.push_value(value),
+            }
         }
-        Ok(append(cell))
+        Ok(Value::list(cell))
     }
 
     pub fn set_global(
@@ -149,7 +534,7 @@ impl<'c> Context<'c> {
         item: &Sym<'c>,
     ) -> Result<Value<'c>> {
         Ok(try_result!(self.symbols.set_global(
-            UniquePointer::read_only(self),
+            Pointer::read_only(self),
             sym,
             item
         )))
@@ -157,19 +542,16 @@ impl<'c> Context<'c> {
 
     pub fn set_local(&mut self, sym: &Symbol<'c>, item: &Sym<'c>) -> Result<Value<'c>> {
         Ok(try_result!(self.symbols.set_local(
-            UniquePointer::read_only(self),
+            Pointer::read_only(self),
             sym,
             item
         )))
     }
 
-    pub fn set_function_local(
-        &mut self,
-        sym: &Symbol<'c>,
-        item: &Sym<'c>,
-    ) -> Result<Value<'c>> {
-        Ok(try_result!(self.symbols.set_function_local(
-            UniquePointer::read_only(self),
+    /// updates the nearest binding of `sym`
+    pub fn setq(&mut self, sym: &Symbol<'c>, item: &Sym<'c>) -> Result<Value<'c>> {
+        Ok(try_result!(self.symbols.setq(
+            Pointer::read_only(self),
             sym,
             item
         )))
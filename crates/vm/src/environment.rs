@@ -0,0 +1,151 @@
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::{Rc, Weak};
+
+use sexprs_data_structures::Symbol;
+
+use crate::{Sym, SymTable};
+
+/// a frame of bindings linked to the frame it was created in.
+///
+/// Cloning an `Environment` shares the frame, so closures capturing
+/// an environment observe later `setq`s to its bindings. Frames refer
+/// to the [global](Environment::global) frame weakly, so that the
+/// globals holding a closure do not keep themselves alive.
+#[derive(Clone, Default)]
+pub struct Environment<'c> {
+    frame: Rc<RefCell<Frame<'c>>>,
+}
+
+#[derive(Default)]
+struct Frame<'c> {
+    symbols: SymTable<'c>,
+    parent: Option<Parent<'c>>,
+    global: bool,
+}
+
+enum Parent<'c> {
+    Frame(Environment<'c>),
+    Global(Weak<RefCell<Frame<'c>>>),
+}
+
+impl<'c> Environment<'c> {
+    pub fn new() -> Environment<'c> {
+        Environment::default()
+    }
+
+    pub fn with_parent(parent: &Environment<'c>) -> Environment<'c> {
+        let parent = if parent.is_global() {
+            Parent::Global(Rc::downgrade(&parent.frame))
+        } else {
+            Parent::Frame(parent.clone())
+        };
+        Environment {
+            frame: Rc::new(RefCell::new(Frame {
+                symbols: SymTable::new(),
+                parent: Some(parent),
+                global: false,
+            })),
+        }
+    }
+
+    pub fn with_symbols(symbols: SymTable<'c>) -> Environment<'c> {
+        Environment {
+            frame: Rc::new(RefCell::new(Frame {
+                symbols,
+                parent: None,
+                global: false,
+            })),
+        }
+    }
+
+    /// the frame of the global bindings of a virtual machine, which
+    /// the frames created within it refer to weakly
+    pub fn global(symbols: SymTable<'c>) -> Environment<'c> {
+        let environment = Environment::with_symbols(symbols);
+        environment.frame.borrow_mut().global = true;
+        environment
+    }
+
+    pub fn is_global(&self) -> bool {
+        self.frame.borrow().global
+    }
+
+    /// true when `self` and `other` are clones of the same frame
+    pub fn is_same(&self, other: &Environment<'c>) -> bool {
+        Rc::ptr_eq(&self.frame, &other.frame)
+    }
+
+    /// the frame this one was created in, `None` for frames without
+    /// a parent and frames of a global frame that was dropped
+    pub fn parent(&self) -> Option<Environment<'c>> {
+        match &self.frame.borrow().parent {
+            Some(Parent::Frame(parent)) => Some(parent.clone()),
+            Some(Parent::Global(frame)) => frame.upgrade().map(|frame| Environment { frame }),
+            None => None,
+        }
+    }
+
+    /// the symbols bound in this frame, without those of its parents
+    pub fn symbols(&self) -> SymTable<'c> {
+        self.frame.borrow().symbols.clone()
+    }
+
+    /// looks `sym` up in this frame then in each parent frame
+    pub fn get(&self, sym: &Symbol<'c>) -> Option<Sym<'c>> {
+        let mut environment = Some(self.clone());
+        while let Some(current) = environment {
+            if let Some(item) = current.frame.borrow().symbols.get(sym) {
+                return Some(item.clone());
+            }
+            environment = current.parent();
+        }
+        None
+    }
+
+    /// binds `sym` in this frame, shadowing bindings of parent frames
+    pub fn define(&self, sym: &Symbol<'c>, item: &Sym<'c>) -> Option<Sym<'c>> {
+        self.frame
+            .borrow_mut()
+            .symbols
+            .insert(sym.clone(), item.clone())
+    }
+
+    /// updates the nearest frame binding `sym` and returns whether
+    /// such a frame was found
+    pub fn set(&self, sym: &Symbol<'c>, item: &Sym<'c>) -> bool {
+        let mut environment = Some(self.clone());
+        while let Some(current) = environment {
+            if let Some(binding) = current.frame.borrow_mut().symbols.get_mut(sym) {
+                *binding = item.clone();
+                return true;
+            }
+            environment = current.parent();
+        }
+        false
+    }
+
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut environment = self.parent();
+        while let Some(current) = environment {
+            depth += 1;
+            environment = current.parent();
+        }
+        depth
+    }
+}
+
+impl<'c> Debug for Environment<'c> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Environment {{
+        depth: {},
+        symbols: {:#?}
+    }}",
+            self.depth(),
+            self.frame.borrow().symbols.keys().collect::<Vec<_>>(),
+        )
+    }
+}
@@ -1,3 +1,4 @@
+use sexprs_parser::Location;
 use sexprs_util::{impl_error, Traceback};
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
@@ -6,6 +7,11 @@ pub enum ErrorType {
     FormatError,
     ParserError,
     RuntimeError,
+    UnboundVariable,
+    UnboundFunction,
+    Signal,
+    NoCatch,
+    ArithmeticError,
 }
 impl std::fmt::Display for ErrorType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -17,11 +23,64 @@ impl std::fmt::Display for ErrorType {
                 Self::FormatError => "FormatError",
                 Self::ParserError => "ParserError",
                 Self::RuntimeError => "RuntimeError",
+                Self::UnboundVariable => "UnboundVariable",
+                Self::UnboundFunction => "UnboundFunction",
+                Self::Signal => "Signal",
+                Self::NoCatch => "NoCatch",
+                Self::ArithmeticError => "ArithmeticError",
             }
         )
     }
 }
-impl_error!(Error, ErrorType);
+impl_error!(Error, ErrorType, {
+    symbol: Option<String>,
+    location: Option<Location>,
+    condition: Option<usize>,
+});
+impl Error {
+    /// the error with the name of the symbol it is about and the
+    /// location of the form it was raised from
+    pub fn with_symbol<T: std::fmt::Display>(mut self, symbol: T, location: Option<Location>) -> Error {
+        self.symbol = Some(symbol.to_string());
+        self.location = location;
+        self
+    }
+
+    /// the name of the symbol the error or the error it was raised
+    /// from is about, such as the unbound symbol of an
+    /// [`ErrorType::UnboundVariable`]
+    pub fn symbol(&self) -> Option<String> {
+        match &self.symbol {
+            Some(symbol) => Some(symbol.clone()),
+            None => self.previous.as_ref().and_then(|error| error.symbol()),
+        }
+    }
+
+    /// the error as unwinding with the condition `id` of the
+    /// [`Context`](crate::Context) that signalled it
+    pub fn with_condition(mut self, id: usize) -> Error {
+        self.condition = Some(id);
+        self
+    }
+
+    /// the id of the condition the error or the error it was raised
+    /// from unwinds with, see [`Context::signal`](crate::Context::signal)
+    pub fn condition(&self) -> Option<usize> {
+        match self.condition {
+            Some(id) => Some(id),
+            None => self.previous.as_ref().and_then(|error| error.condition()),
+        }
+    }
+
+    /// where in the source the error or the error it was raised from
+    /// occurred
+    pub fn location(&self) -> Option<Location> {
+        match &self.location {
+            Some(location) => Some(location.clone()),
+            None => self.previous.as_ref().and_then(|error| error.location()),
+        }
+    }
+}
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::new(e, ErrorType::IOError)
@@ -1,13 +1,15 @@
+use std::any::TypeId;
 use std::fmt::{Debug, Display, Formatter};
 use std::iter::Zip;
 
 use sexprs_data_structures::{
-    AsSymbol, Symbol, Value, ValueIterator,
+    AsSymbol, Callable, Quotable, Symbol, Value, ValueIterator,
 };
 use sexprs_util::{try_result, with_caller, admonition, warn};
-use unique_pointer::UniquePointer;
 
-use crate::{runtime_error, BuiltinFunction, Context, Result, Sym};
+use crate::{
+    runtime_error, BuiltinFunction, Context, Environment, Pointer, Result, StackFrame, Sym,
+};
 
 #[derive(Clone)]
 pub enum Function<'c> {
@@ -20,8 +22,29 @@ pub enum Function<'c> {
         args: Value<'c>,
         body: Value<'c>,
     },
+    Macro {
+        name: Symbol<'c>,
+        args: Value<'c>,
+        body: Value<'c>,
+    },
+    Closure {
+        name: Symbol<'c>,
+        args: Value<'c>,
+        body: Value<'c>,
+        /// the environment the closure was made in, `None` for the
+        /// global environment
+        env: Option<Environment<'c>>,
+    },
 }
 impl<'c> Function<'c> {
+    pub fn name(&self) -> Symbol<'c> {
+        match self {
+            Function::Builtin { name, .. }
+            | Function::Defun { name, .. }
+            | Function::Macro { name, .. }
+            | Function::Closure { name, .. } => name.clone(),
+        }
+    }
     pub fn is_builtin(&self) -> bool {
         match self {
             Function::Builtin {..} => true,
@@ -34,6 +57,18 @@ impl<'c> Function<'c> {
             _ => false
         }
     }
+    pub fn is_macro(&self) -> bool {
+        match self {
+            Function::Macro {..} => true,
+            _ => false
+        }
+    }
+    pub fn is_closure(&self) -> bool {
+        match self {
+            Function::Closure {..} => true,
+            _ => false
+        }
+    }
     pub fn validate_args(
         &self,
         name: &Symbol<'c>,
@@ -60,43 +95,114 @@ impl<'c> Function<'c> {
 
     pub fn bind_args_to_local_context(
         &self,
-        mut vm: UniquePointer<Context<'c>>,
+        mut vm: Pointer<Context<'c>>,
         name: &Symbol<'c>,
         expected: &Value<'c>,
         received: &Value<'c>,
     ) -> Result<Vec<(Symbol<'c>, Value<'c>)>> {
+        let parameters = expected.values();
+        let received = received.values();
+        let (required, rest) = rest_parameter(&parameters);
+        match rest {
+            Some(_) if received.len() < required.len() => {
+                return Err(with_caller!(runtime_error(
+                    format!(
+                        "{} expected at least {} args but received {}",
+                        name,
+                        required.len(),
+                        received.len()
+                    ),
+                    None
+                )));
+            },
+            None if received.len() != required.len() => {
+                return Err(with_caller!(runtime_error(
+                    format!(
+                        "{} expected {} args [{:#?}] but received {}: {:#?}",
+                        name,
+                        required.len(),
+                        expected,
+                        received.len(),
+                        Value::from_iter(received)
+                    ),
+                    None
+                )));
+            },
+            _ => {},
+        }
+        let (values, remaining) = received.split_at(required.len());
         let mut args = Vec::<(Symbol<'c>, Value<'c>)>::new();
-        for (symbol, value) in try_result!(self.validate_args(name, expected, received))
-        {
+        for (symbol, value) in required.iter().zip(values) {
             args.push((symbol.as_symbol(), value.clone()));
-            try_result!(vm
-                .inner_mut()
-                .set_function_local(&symbol.as_symbol(), &Sym::Value(value.clone())));
+        }
+        if let Some(rest) = rest {
+            args.push((
+                rest,
+                if remaining.is_empty() {
+                    Value::nil()
+                } else {
+                    Value::from_iter(remaining.to_vec())
+                },
+            ));
+        }
+        let environment = vm.inner_ref().symbols.environment();
+        for (symbol, value) in &args {
+            environment.define(symbol, &Sym::Value(value.clone()));
         }
         Ok(args)
     }
 
+    fn eval_body(
+        &self,
+        mut vm: Pointer<Context<'c>>,
+        name: &Symbol<'c>,
+        args: &Value<'c>,
+        body: &Value<'c>,
+        list: &Value<'c>,
+    ) -> Result<Value<'c>> {
+        try_result!(self.bind_args_to_local_context(vm.clone(), name, args, list));
+        let mut forms = body.values();
+        let last = forms.pop().unwrap_or_default();
+        for form in forms {
+            try_result!(vm.inner_mut().eval(form));
+        }
+        Ok(try_result!(vm.inner_mut().eval_in_tail_position(last)))
+    }
+
+    /// evaluates the body in a new frame whose parent is `parent`,
+    /// restoring the caller's environment afterwards
+    fn eval_in_frame(
+        &self,
+        mut vm: Pointer<Context<'c>>,
+        parent: &Environment<'c>,
+        name: &Symbol<'c>,
+        args: &Value<'c>,
+        body: &Value<'c>,
+        list: &Value<'c>,
+    ) -> Result<Value<'c>> {
+        let caller = vm
+            .inner_mut()
+            .symbols
+            .enter(Environment::with_parent(parent));
+        let value = self.eval_body(vm.clone(), name, args, body, list);
+        vm.inner_mut().symbols.leave(caller);
+        value
+    }
+
     pub fn call(
         &self,
-        mut vm: UniquePointer<Context<'c>>,
+        mut vm: Pointer<Context<'c>>,
         list: Value<'c>,
     ) -> Result<Value<'c>> {
         match self {
-            Function::Defun { name, args, body } => {
-                // warn!(82, "calling {}", name);
-                // dbg!(&list);
-                try_result!(self.bind_args_to_local_context(
-                    vm.clone(),
-                    name,
-                    args,
-                    &list
-                ));
-
-                let mut value = Value::nil();
-                for (_, val) in body.into_iter().enumerate() {
-                    value = try_result!(vm.inner_mut().eval(val));
-                }
-                Ok(value)
+            Function::Defun { .. } | Function::Closure { .. } => {
+                let form = vm.inner_mut().call_form.take();
+                let list = try_result!(vm.inner_mut().eval_arguments(list));
+                Ok(try_result!(self.apply_from(vm, list, form)))
+            },
+            Function::Macro { .. } => {
+                let expansion = try_result!(self.expand(vm.clone(), list));
+                Ok(try_result!(vm.inner_mut().eval(expansion)))
             },
             Function::Builtin { name, function, .. } => {
                 // warn!(74, "calling {}", name);
@@ -106,6 +212,165 @@ impl<'c> Function<'c> {
             },
         }
     }
+
+    /// calls the function with already evaluated arguments, as done
+    /// by `funcall` and `apply`.
+    pub fn apply(
+        &self,
+        vm: Pointer<Context<'c>>,
+        list: Value<'c>,
+    ) -> Result<Value<'c>> {
+        self.apply_from(vm, list, None)
+    }
+
+    /// like [`Function::apply`] but records `form` as the call
+    /// expression of the frame pushed onto the lisp call stack.
+    ///
+    /// When the call fails the stack is saved as the backtrace of
+    /// the error, see [`Context::backtrace`].
+    fn apply_from(
+        &self,
+        mut vm: Pointer<Context<'c>>,
+        list: Value<'c>,
+        form: Option<Value<'c>>,
+    ) -> Result<Value<'c>> {
+        if !self.is_defun() && !self.is_closure() {
+            return self.apply_builtin(vm, list);
+        }
+        let depth = vm.inner_ref().frames.len();
+        let frame = vm.inner_ref().frame(self.name(), list.clone(), form);
+        vm.inner_mut().frames.push(frame);
+        let value = self.trampoline(vm.clone(), list);
+        if value.is_err() && vm.inner_ref().backtrace.is_none() {
+            let frames = vm.inner_ref().frames.iter().rev().cloned().collect();
+            vm.inner_mut().backtrace = Some(frames);
+        }
+        vm.inner_mut().frames.truncate(depth);
+        value
+    }
+
+    /// runs lisp functions in a loop: a call in tail position of the
+    /// body is deferred through [`Context::tail_call`], so tail
+    /// recursion uses bounded native stack.
+    ///
+    /// The first tail call pushes a frame on top of the caller's and
+    /// the following ones replace it, so the lisp call stack keeps the
+    /// frame the chain of tail calls started from.
+    fn trampoline(
+        &self,
+        mut vm: Pointer<Context<'c>>,
+        list: Value<'c>,
+    ) -> Result<Value<'c>> {
+        let mut next = None::<Function<'c>>;
+        let mut list = list;
+        loop {
+            let function = next.as_ref().unwrap_or(self);
+            let value = match function {
+                Function::Defun { name, args, body, .. } => {
+                    let globals = vm.inner_ref().symbols.globals.clone();
+                    try_result!(function.eval_in_frame(vm.clone(), &globals, name, args, body, &list))
+                },
+                Function::Closure { name, args, body, env, .. } => {
+                    let env = match env {
+                        Some(env) => env.clone(),
+                        None => vm.inner_ref().symbols.globals.clone(),
+                    };
+                    try_result!(function.eval_in_frame(vm.clone(), &env, name, args, body, &list))
+                },
+                function => return function.apply_builtin(vm, list),
+            };
+            match vm.inner_mut().tail_call.take() {
+                Some((function, args, form)) => {
+                    let mut frame = vm.inner_ref().frame(function.name(), args.clone(), form);
+                    frame.tail = true;
+                    let frames = &mut vm.inner_mut().frames;
+                    match frames.last_mut() {
+                        Some(top) if top.tail => *top = frame,
+                        _ => frames.push(frame),
+                    }
+                    next = Some(function);
+                    list = args;
+                },
+                None => return Ok(value),
+            }
+        }
+    }
+
+    fn apply_builtin(
+        &self,
+        mut vm: Pointer<Context<'c>>,
+        list: Value<'c>,
+    ) -> Result<Value<'c>> {
+        match self {
+            Function::Builtin { function, .. } => {
+                // builtins evaluate their own arguments, quoting keeps
+                // the values from being evaluated a second time
+                let list = Value::from_iter(list.values().iter().map(Value::quote));
+                let outer = std::mem::replace(&mut vm.inner_mut().tail_form, false);
+                let value = function(vm.clone(), list);
+                vm.inner_mut().tail_form = outer;
+                Ok(try_result!(value))
+            },
+            Function::Macro { name, .. } => Err(with_caller!(runtime_error(
+                format!("cannot apply macro {}", name),
+                None
+            ))),
+            Function::Defun { .. } | Function::Closure { .. } => self.apply(vm, list),
+        }
+    }
+}
+
+impl<'c> Function<'c> {
+    /// binds the unevaluated arguments of a macro call and evaluates
+    /// the macro body, returning the expansion as an evaluable form
+    pub fn expand(
+        &self,
+        mut vm: Pointer<Context<'c>>,
+        list: Value<'c>,
+    ) -> Result<Value<'c>> {
+        match self {
+            Function::Macro { name, args, body, .. } => {
+                let globals = vm.inner_ref().symbols.globals.clone();
+                let expansion =
+                    try_result!(self.eval_in_frame(vm, &globals, name, args, body, &list));
+                Ok(Quotable::unquote(&expansion))
+            },
+            function => Err(with_caller!(runtime_error(
+                format!("{} is not a macro", function),
+                None
+            ))),
+        }
+    }
+}
+
+/// splits the parameters of a lambda list such as `(a b &rest c)`
+/// into the required ones and the symbol bound to the remaining
+/// arguments
+fn rest_parameter<'a, 'c>(parameters: &'a [Value<'c>]) -> (&'a [Value<'c>], Option<Symbol<'c>>) {
+    let position = parameters.iter().position(|parameter| match parameter {
+        Value::Symbol(symbol) => ["&rest", "&body"].contains(&symbol.symbol()),
+        _ => false,
+    });
+    match position.and_then(|position| Some((position, parameters.get(position + 1)?))) {
+        Some((position, rest)) => (&parameters[..position], Some(rest.as_symbol())),
+        None => (parameters, None),
+    }
+}
+
+// SAFETY: no other type returns the id of `Function`
+unsafe impl<'c> Callable<'c> for Function<'c> {
+    fn static_type_id() -> TypeId {
+        TypeId::of::<Function<'static>>()
+    }
+
+    fn callable_type_id(&self) -> TypeId {
+        TypeId::of::<Function<'static>>()
+    }
+}
+
+/// the forms of `body` each preceded by a space
+fn splice<'c>(body: &Value<'c>) -> String {
+    body.values().iter().map(|form| format!(" {}", form)).collect()
 }
 
 impl<'c> Display for Function<'c> {
@@ -114,8 +379,12 @@ impl<'c> Display for Function<'c> {
             f,
             "{}",
             match self {
-                Function::Defun { name, args, body } =>
+                Function::Defun { name, args, body, .. } =>
                     format!("(defun {} {} {})", name, args, body),
+                Function::Macro { name, args, body, .. } =>
+                    format!("(defmacro {} {} {})", name, args, body),
+                Function::Closure { args, body, .. } =>
+                    format!("(lambda {}{})", args, splice(body)),
                 Function::Builtin { name, function } =>
                     format!("builtin-function {} {:#?}", name, function),
             }
@@ -129,8 +398,12 @@ impl<'c> Debug for Function<'c> {
             f,
             "{}",
             match self {
-                Function::Defun { name, args, body } =>
+                Function::Defun { name, args, body, .. } =>
                     format!("(defun {} {} {})", name, args, body),
+                Function::Macro { name, args, body, .. } =>
+                    format!("(defmacro {} {} {})", name, args, body),
+                Function::Closure { args, body, .. } =>
+                    format!("(lambda {}{})", args, splice(body)),
                 Function::Builtin { name, function } =>
                     format!("builtin-function {} {:#?}", name, function),
             }
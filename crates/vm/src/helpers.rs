@@ -1,3 +1,5 @@
+use sexprs_data_structures::Symbol;
+use sexprs_parser::Location;
 use sexprs_util::with_caller;
 
 use crate::{Error, ErrorType};
@@ -5,3 +7,29 @@ use crate::{Error, ErrorType};
 pub fn runtime_error(message: String, previous: Option<Error>) -> Error {
     with_caller!(Error::with_previous_error(message, ErrorType::RuntimeError, previous))
 }
+
+/// error for arithmetic that has no result, such as a division by
+/// zero or an integer overflow
+pub fn arithmetic_error(message: String) -> Error {
+    with_caller!(Error::new(message, ErrorType::ArithmeticError))
+}
+
+/// error for a symbol evaluated as a variable without being bound,
+/// raised from the form at `location` when the source is known
+pub fn unbound_variable(symbol: &Symbol, location: Option<Location>) -> Error {
+    unbound(symbol, location, "variable", ErrorType::UnboundVariable)
+}
+
+/// error for a call to a symbol that is not bound to a function
+pub fn unbound_function(symbol: &Symbol, location: Option<Location>) -> Error {
+    unbound(symbol, location, "function", ErrorType::UnboundFunction)
+}
+
+fn unbound(symbol: &Symbol, location: Option<Location>, kind: &str, ty: ErrorType) -> Error {
+    let at = location
+        .as_ref()
+        .map(|location| format!(" at {}", location))
+        .unwrap_or_default();
+    with_caller!(Error::new(format!("unbound {} {}{}", kind, symbol, at), ty))
+        .with_symbol(symbol, location)
+}
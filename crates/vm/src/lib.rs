@@ -1,6 +1,8 @@
 #![allow(unused)]
 pub use errors::{Error, ErrorType, Result};
 pub mod builtin;
+pub mod condition;
+pub use condition::Condition;
 pub mod errors;
 pub mod helpers;
 pub mod sym;
@@ -11,8 +13,14 @@ pub use builtin::BuiltinFunction;
 pub use function::Function;
 pub use helpers::runtime_error;
 pub mod virtual_machine;
-pub use virtual_machine::VirtualMachine;
+pub use virtual_machine::{VirtualMachine, PRELUDE};
+pub mod environment;
+pub use environment::Environment;
 pub mod table;
 pub use table::{SymTable, SymbolTable};
 pub mod context;
 pub use context::Context;
+pub mod pointer;
+pub use pointer::Pointer;
+pub mod stack;
+pub use stack::{Backtrace, StackFrame};
@@ -0,0 +1,55 @@
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+
+/// a pointer to a value owned elsewhere, such as the [`Context`]
+/// passed to every builtin.
+///
+/// Unlike `UniquePointer::read_only` it allocates nothing, so a
+/// pointer made or cloned for each call costs no memory.
+///
+/// [`Context`]: crate::Context
+pub struct Pointer<T> {
+    ptr: *mut T,
+}
+
+impl<T> Pointer<T> {
+    pub fn read_only(data: &T) -> Pointer<T> {
+        Pointer {
+            ptr: (data as *const T).cast_mut(),
+        }
+    }
+
+    pub fn inner_ref<'a>(&self) -> &'a T {
+        unsafe { &*self.ptr }
+    }
+
+    pub fn inner_mut<'a>(&mut self) -> &'a mut T {
+        unsafe { &mut *self.ptr }
+    }
+}
+
+impl<T> Clone for Pointer<T> {
+    fn clone(&self) -> Pointer<T> {
+        Pointer { ptr: self.ptr }
+    }
+}
+
+impl<T> Deref for Pointer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.inner_ref()
+    }
+}
+
+impl<T> DerefMut for Pointer<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.inner_mut()
+    }
+}
+
+impl<T: Debug> Debug for Pointer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Pointer({:#?})", self.inner_ref())
+    }
+}
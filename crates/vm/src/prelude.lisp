@@ -0,0 +1,32 @@
+;;; prelude.lisp --- definitions every virtual machine starts with
+;;;
+;;; Evaluated by `VirtualMachine::new` after the builtins are
+;;; registered. `VirtualMachine::with_prelude` evaluates more
+;;; definitions after it, `VirtualMachine::with_custom_prelude`
+;;; evaluates others instead of it and `VirtualMachine::bare` skips
+;;; it.
+
+;;; list accessors
+
+(defun caar (list) (car (car list)))
+(defun cadr (list) (car (cdr list)))
+(defun cdar (list) (cdr (car list)))
+(defun cddr (list) (cdr (cdr list)))
+(defun caddr (list) (car (cddr list)))
+
+;;; control
+
+(defmacro when (condition &rest body)
+  `(if ,condition (progn ,@body)))
+
+(defmacro unless (condition &rest body)
+  `(if ,condition nil (progn ,@body)))
+
+;;; trees
+
+;; the atoms of `tree' in order, at any depth, without the nils
+;; ending its lists
+(defun flatten (tree)
+  (cond ((null tree) nil)
+        ((listp tree) (append (flatten (car tree)) (flatten (cdr tree))))
+        (t (list tree))))
@@ -0,0 +1,95 @@
+use std::fmt::{Debug, Display, Formatter};
+
+use sexprs_data_structures::{Symbol, Value};
+use sexprs_parser::Location;
+
+/// a call to a lisp function on the lisp call stack
+#[derive(Clone)]
+pub struct StackFrame<'c> {
+    pub name: Symbol<'c>,
+    /// the evaluated arguments of the call
+    pub args: Value<'c>,
+    /// the call expression, `None` for calls through `funcall` or
+    /// `apply`
+    pub form: Option<Value<'c>>,
+    /// where the call expression was read from
+    pub location: Option<Location>,
+    /// whether the call was made in tail position, see
+    /// [`Function::trampoline`](crate::Function)
+    pub tail: bool,
+}
+
+impl<'c> StackFrame<'c> {
+    pub fn new(
+        name: Symbol<'c>,
+        args: Value<'c>,
+        form: Option<Value<'c>>,
+        location: Option<Location>,
+    ) -> StackFrame<'c> {
+        StackFrame {
+            name,
+            args,
+            form,
+            location,
+            tail: false,
+        }
+    }
+
+    /// the location of the call expression
+    pub fn call_site(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+}
+
+impl<'c> Display for StackFrame<'c> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self.call_site() {
+            Some(location) => write!(f, "{} at {}", self.name, location),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl<'c> Debug for StackFrame<'c> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "StackFrame {{ name: {}, args: {} }}", self.name, self.args)
+    }
+}
+
+/// the lisp call stack at the point an error was raised, innermost
+/// call first
+#[derive(Clone, Debug, Default)]
+pub struct Backtrace<'c> {
+    pub frames: Vec<StackFrame<'c>>,
+}
+
+impl<'c> Backtrace<'c> {
+    pub fn new(frames: Vec<StackFrame<'c>>) -> Backtrace<'c> {
+        Backtrace { frames }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl<'c> Display for Backtrace<'c> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        if self.is_empty() {
+            return Ok(());
+        }
+        write!(
+            f,
+            "in {}",
+            self.frames
+                .iter()
+                .map(|frame| frame.to_string())
+                .collect::<Vec<String>>()
+                .join(" ← called from ")
+        )
+    }
+}
@@ -2,7 +2,7 @@ use std::cmp::{Ordering, PartialEq, PartialOrd};
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 
-use sexprs_data_structures::{append, Value};
+use sexprs_data_structures::{Procedure, Value};
 
 use crate::Function;
 
@@ -41,10 +41,13 @@ impl<'c> Sym<'c> {
         match self {
             Sym::Value(value) => value.clone(),
             Sym::Function(Function::Builtin { name, .. }) => Value::symbol(name),
-            Sym::Function(Function::Defun { name, args, body }) => Value::list([
+            Sym::Function(function @ Function::Closure { .. }) =>
+                Value::Procedure(Procedure::new(function.clone())),
+            Sym::Function(Function::Defun { name, args, body, .. })
+            | Sym::Function(Function::Macro { name, args, body, .. }) => Value::list([
                 Value::from(name),
                 args.clone(),
-                append(body.clone()),
+                body.clone(),
             ]),
         }
     }
@@ -1,19 +1,30 @@
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Display};
+use std::rc::Rc;
 
 use sexprs_data_structures::{AsValue, Symbol, Value};
+use sexprs_parser::{Location, Locations};
 use sexprs_util::try_result;
-use unique_pointer::UniquePointer;
 
-use crate::{builtin, BuiltinFunction, Context, Function, Result, Sym};
+use crate::helpers::unbound_variable;
+use crate::{
+    builtin, BuiltinFunction, Context, Environment, Function, Pointer, Result, Sym,
+};
 
 pub type SymTable<'c> = BTreeMap<Symbol<'c>, Sym<'c>>;
 
 #[derive(Clone)]
 pub struct SymbolTable<'c> {
-    pub(crate) globals: SymTable<'c>,
-    pub(crate) locals: SymTable<'c>,
-    pub(crate) function_locals: SymTable<'c>,
+    pub(crate) globals: Environment<'c>,
+    pub(crate) environment: Environment<'c>,
+    pub(crate) self_evaluating_symbols: bool,
+    /// the property lists of symbols, shared between clones like the
+    /// global environment
+    pub(crate) plists: Rc<RefCell<BTreeMap<Symbol<'c>, Value<'c>>>>,
+    /// where the lists of the evaluated programs were read, shared
+    /// between clones like the property lists
+    pub(crate) locations: Rc<RefCell<Locations<'c>>>,
 }
 impl<'c> Debug for SymbolTable<'c> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -21,9 +32,9 @@ impl<'c> Debug for SymbolTable<'c> {
             f,
             "SymbolTable {{
         globals: {:#?},
-        locals: {:#?}
+        environment: {:#?}
     }}",
-            &self.globals, &self.locals,
+            &self.globals, &self.environment,
         )
     }
 }
@@ -33,10 +44,9 @@ impl<'c> Display for SymbolTable<'c> {
             f,
             "SymbolTable {{
         globals: {:#?},
-        function_locals: {:#?},
-        locals: {:#?}
+        environment: {:#?}
     }}",
-            &self.globals, &self.function_locals, &self.locals,
+            &self.globals.symbols(), &self.environment.symbols(),
         )
     }
 }
@@ -52,91 +62,285 @@ impl<'c> SymbolTable<'c> {
 
         register_builtin_function(&mut globals, "setq", builtin::state::setq);
         register_builtin_function(&mut globals, "defun", builtin::state::defun);
+        register_builtin_function(&mut globals, "defmacro", builtin::state::defmacro);
+        register_builtin_function(&mut globals, "lambda", builtin::state::lambda);
+
+        register_builtin_function(&mut globals, "let", builtin::binding::r#let);
+        register_builtin_function(&mut globals, "let*", builtin::binding::let_star);
+        register_builtin_function(&mut globals, "letrec", builtin::binding::letrec);
+        register_builtin_function(&mut globals, "flet", builtin::binding::flet);
+        register_builtin_function(&mut globals, "labels", builtin::binding::labels);
+
+        register_builtin_function(&mut globals, "funcall", builtin::state::funcall);
+        register_builtin_function(&mut globals, "apply", builtin::state::apply);
+        register_builtin_function(&mut globals, "macroexpand", builtin::state::macroexpand);
+        register_builtin_function(&mut globals, "macroexpand-1", builtin::state::macroexpand_1);
+        register_builtin_function(&mut globals, "macroexpand-all", builtin::state::macroexpand_all);
 
         register_builtin_function(&mut globals, "car", builtin::list::car);
         register_builtin_function(&mut globals, "cdr", builtin::list::cdr);
         register_builtin_function(&mut globals, "cons", builtin::list::cons);
+        register_builtin_function(&mut globals, "setcar", builtin::list::setcar);
+        register_builtin_function(&mut globals, "setcdr", builtin::list::setcdr);
         register_builtin_function(&mut globals, "list", builtin::list::list);
         register_builtin_function(&mut globals, "append", builtin::list::append);
         register_builtin_function(&mut globals, "quote", builtin::list::quote);
-        register_builtin_function(&mut globals, "print", builtin::string::print);
         register_builtin_function(&mut globals, "backquote", builtin::list::backquote);
+        register_builtin_function(&mut globals, "unquote", builtin::list::unquote);
+        register_builtin_function(&mut globals, "unquote-splicing", builtin::list::unquote_splicing);
 
         register_builtin_function(&mut globals, "if", builtin::r#if::r#if);
+        register_builtin_function(&mut globals, "cond", builtin::control::cond);
+        register_builtin_function(&mut globals, "and", builtin::control::and);
+        register_builtin_function(&mut globals, "or", builtin::control::or);
+        register_builtin_function(&mut globals, "progn", builtin::control::progn);
+        register_builtin_function(&mut globals, "prog1", builtin::control::prog1);
+        register_builtin_function(&mut globals, "case", builtin::control::case);
+        register_builtin_function(&mut globals, "while", builtin::control::r#while);
+        register_builtin_function(&mut globals, "dolist", builtin::control::dolist);
+        register_builtin_function(&mut globals, "dotimes", builtin::control::dotimes);
+
+        register_builtin_function(&mut globals, "signal", builtin::condition::signal);
+        register_builtin_function(&mut globals, "error", builtin::condition::error);
+        register_builtin_function(&mut globals, "condition-case", builtin::condition::condition_case);
+        register_builtin_function(&mut globals, "unwind-protect", builtin::condition::unwind_protect);
+        register_builtin_function(&mut globals, "ignore-errors", builtin::condition::ignore_errors);
+        register_builtin_function(&mut globals, "catch", builtin::condition::catch);
+        register_builtin_function(&mut globals, "throw", builtin::condition::throw);
 
         register_builtin_function(&mut globals, "listp", builtin::r#type::listp);
         register_builtin_function(&mut globals, "null", builtin::r#type::null);
 
+        register_builtin_function(&mut globals, "print", builtin::string::print);
+        register_builtin_function(&mut globals, "format", builtin::string::format::format);
+        register_builtin_function(&mut globals, "concat", builtin::string::operation::concat);
+        register_builtin_function(&mut globals, "substring", builtin::string::operation::substring);
+        register_builtin_function(&mut globals, "string-length", builtin::string::operation::string_length);
+        register_builtin_function(&mut globals, "string-upcase", builtin::string::operation::string_upcase);
+        register_builtin_function(&mut globals, "string-downcase", builtin::string::operation::string_downcase);
+        register_builtin_function(&mut globals, "string-trim", builtin::string::operation::string_trim);
+        register_builtin_function(&mut globals, "split-string", builtin::string::operation::split_string);
+        register_builtin_function(&mut globals, "string-join", builtin::string::operation::string_join);
+        register_builtin_function(&mut globals, "string-prefix-p", builtin::string::operation::string_prefix_p);
+        register_builtin_function(&mut globals, "string-suffix-p", builtin::string::operation::string_suffix_p);
+        register_builtin_function(&mut globals, "string-search", builtin::string::operation::string_search);
+        register_builtin_function(&mut globals, "string-replace", builtin::string::operation::string_replace);
+        register_builtin_function(&mut globals, "number-to-string", builtin::string::conversion::number_to_string);
+        register_builtin_function(&mut globals, "string-to-number", builtin::string::conversion::string_to_number);
+        register_builtin_function(&mut globals, "symbol-name", builtin::string::conversion::symbol_name);
+        register_builtin_function(&mut globals, "intern", builtin::string::conversion::intern);
+        register_builtin_function(&mut globals, "char-to-string", builtin::string::conversion::char_to_string);
+        register_builtin_function(&mut globals, "string-to-list", builtin::string::conversion::string_to_list);
+        register_builtin_function(&mut globals, "char-code", builtin::string::conversion::char_code);
+        register_builtin_function(&mut globals, "code-char", builtin::string::conversion::code_char);
+
+        register_builtin_function(&mut globals, "mapcar", builtin::sequence::mapcar);
+        register_builtin_function(&mut globals, "mapc", builtin::sequence::mapc);
+        register_builtin_function(&mut globals, "mapcan", builtin::sequence::mapcan);
+        register_builtin_function(&mut globals, "seq-filter", builtin::sequence::seq_filter);
+        register_builtin_function(&mut globals, "remove-if", builtin::sequence::remove_if);
+        register_builtin_function(&mut globals, "reduce", builtin::sequence::reduce);
+        register_builtin_function(&mut globals, "fold", builtin::sequence::fold);
+        register_builtin_function(&mut globals, "sort", builtin::sequence::sort);
+        register_builtin_function(&mut globals, "reverse", builtin::sequence::reverse);
+        register_builtin_function(&mut globals, "nth", builtin::sequence::nth);
+        register_builtin_function(&mut globals, "nthcdr", builtin::sequence::nthcdr);
+        register_builtin_function(&mut globals, "last", builtin::sequence::last);
+        register_builtin_function(&mut globals, "butlast", builtin::sequence::butlast);
+        register_builtin_function(&mut globals, "length", builtin::sequence::length);
+        register_builtin_function(&mut globals, "member", builtin::sequence::member);
+        register_builtin_function(&mut globals, "delete", builtin::sequence::delete);
+        register_builtin_function(&mut globals, "remove", builtin::sequence::remove);
+        register_builtin_function(&mut globals, "number-sequence", builtin::sequence::number_sequence);
+        register_builtin_function(&mut globals, "seq-uniq", builtin::sequence::seq_uniq);
+
+        register_builtin_function(&mut globals, "make-vector", builtin::vector::make_vector);
+        register_builtin_function(&mut globals, "vector", builtin::vector::vector);
+        register_builtin_function(&mut globals, "aref", builtin::vector::aref);
+        register_builtin_function(&mut globals, "aset", builtin::vector::aset);
+        register_builtin_function(&mut globals, "vector-length", builtin::vector::vector_length);
+        register_builtin_function(&mut globals, "vconcat", builtin::vector::vconcat);
+        register_builtin_function(&mut globals, "vector-to-list", builtin::vector::vector_to_list);
+        register_builtin_function(&mut globals, "list-to-vector", builtin::vector::list_to_vector);
+        register_builtin_function(&mut globals, "list-to-bytes", builtin::vector::list_to_bytes);
+
+        register_builtin_function(&mut globals, "make-hash-table", builtin::hash_table::make_hash_table);
+        register_builtin_function(&mut globals, "gethash", builtin::hash_table::gethash);
+        register_builtin_function(&mut globals, "puthash", builtin::hash_table::puthash);
+        register_builtin_function(&mut globals, "remhash", builtin::hash_table::remhash);
+        register_builtin_function(&mut globals, "maphash", builtin::hash_table::maphash);
+        register_builtin_function(&mut globals, "hash-table-count", builtin::hash_table::hash_table_count);
+        register_builtin_function(&mut globals, "hash-table-keys", builtin::hash_table::hash_table_keys);
+
+        register_builtin_function(&mut globals, "assoc", builtin::alist::assoc);
+        register_builtin_function(&mut globals, "assq", builtin::alist::assq);
+        register_builtin_function(&mut globals, "rassoc", builtin::alist::rassoc);
+        register_builtin_function(&mut globals, "alist-get", builtin::alist::alist_get);
+        register_builtin_function(&mut globals, "plist-get", builtin::plist::plist_get);
+        register_builtin_function(&mut globals, "plist-put", builtin::plist::plist_put);
+        register_builtin_function(&mut globals, "plist-member", builtin::plist::plist_member);
+        register_builtin_function(&mut globals, "get", builtin::plist::get);
+        register_builtin_function(&mut globals, "put", builtin::plist::put);
+        register_builtin_function(&mut globals, "symbol-plist", builtin::plist::symbol_plist);
+
+        register_builtin_function(&mut globals, "eq", builtin::equality::eq);
+        register_builtin_function(&mut globals, "eql", builtin::equality::eql);
+        register_builtin_function(&mut globals, "equal", builtin::equality::equal);
+
         register_builtin_function(&mut globals, "*", builtin::math::arithmetic::mul);
         register_builtin_function(&mut globals, "+", builtin::math::arithmetic::add);
         register_builtin_function(&mut globals, "-", builtin::math::arithmetic::sub);
         register_builtin_function(&mut globals, "/", builtin::math::arithmetic::div);
+        register_builtin_function(&mut globals, "abs", builtin::math::arithmetic::abs);
+
+        register_builtin_function(&mut globals, "=", builtin::math::comparison::equal);
+        register_builtin_function(&mut globals, "/=", builtin::math::comparison::not_equal);
+        register_builtin_function(&mut globals, "<", builtin::math::comparison::less);
+        register_builtin_function(&mut globals, ">", builtin::math::comparison::greater);
+        register_builtin_function(&mut globals, "<=", builtin::math::comparison::less_or_equal);
+        register_builtin_function(&mut globals, ">=", builtin::math::comparison::greater_or_equal);
+        register_builtin_function(&mut globals, "min", builtin::math::comparison::min);
+        register_builtin_function(&mut globals, "max", builtin::math::comparison::max);
+
+        register_builtin_function(&mut globals, "floor", builtin::math::rounding::floor);
+        register_builtin_function(&mut globals, "ceiling", builtin::math::rounding::ceiling);
+        register_builtin_function(&mut globals, "round", builtin::math::rounding::round);
+        register_builtin_function(&mut globals, "truncate", builtin::math::rounding::truncate);
+        register_builtin_function(&mut globals, "mod", builtin::math::rounding::r#mod);
+        register_builtin_function(&mut globals, "rem", builtin::math::rounding::rem);
+
+        register_builtin_function(&mut globals, "expt", builtin::math::transcendental::expt);
+        register_builtin_function(&mut globals, "sqrt", builtin::math::transcendental::sqrt);
+        register_builtin_function(&mut globals, "exp", builtin::math::transcendental::exp);
+        register_builtin_function(&mut globals, "log", builtin::math::transcendental::log);
+        register_builtin_function(&mut globals, "sin", builtin::math::transcendental::sin);
+        register_builtin_function(&mut globals, "cos", builtin::math::transcendental::cos);
+        register_builtin_function(&mut globals, "tan", builtin::math::transcendental::tan);
+        register_builtin_function(&mut globals, "asin", builtin::math::transcendental::asin);
+        register_builtin_function(&mut globals, "acos", builtin::math::transcendental::acos);
+        register_builtin_function(&mut globals, "atan", builtin::math::transcendental::atan);
+
+        register_builtin_function(&mut globals, "zerop", builtin::math::predicate::zerop);
+        register_builtin_function(&mut globals, "evenp", builtin::math::predicate::evenp);
+        register_builtin_function(&mut globals, "oddp", builtin::math::predicate::oddp);
+
+        register_builtin_function(&mut globals, "logand", builtin::math::bitwise::logand);
+        register_builtin_function(&mut globals, "logior", builtin::math::bitwise::logior);
+        register_builtin_function(&mut globals, "logxor", builtin::math::bitwise::logxor);
+        register_builtin_function(&mut globals, "ash", builtin::math::bitwise::ash);
+
+        globals.extend(locals);
+        let globals = Environment::global(globals);
+        SymbolTable {
+            environment: globals.clone(),
+            globals,
+            self_evaluating_symbols: false,
+            plists: Rc::default(),
+            locations: Rc::default(),
+        }
+    }
+
+    /// the symbols bound in the global frame
+    pub fn globals(&self) -> SymTable<'c> {
+        self.globals.symbols()
+    }
 
-        let table = SymbolTable {
-            globals: globals.clone(),
-            function_locals: SymTable::new(),
-            locals,
-        };
-        table
+    pub fn environment(&self) -> Environment<'c> {
+        self.environment.clone()
     }
 
-    pub fn extend(&mut self, other: Self) {
-        self.globals.extend(other.globals.clone());
-        self.locals.extend(other.locals.clone());
+    /// makes `environment` the current environment and returns the
+    /// previous one, to be restored with [`SymbolTable::leave`]
+    pub fn enter(&mut self, environment: Environment<'c>) -> Environment<'c> {
+        std::mem::replace(&mut self.environment, environment)
+    }
+
+    pub fn leave(&mut self, previous: Environment<'c>) {
+        self.environment = previous;
     }
 
     pub fn set_global(
         &mut self,
-        context: UniquePointer<Context<'c>>,
+        _context: Pointer<Context<'c>>,
         sym: &Symbol<'c>,
         item: &Sym<'c>,
     ) -> Result<Value<'c>> {
-        let previous =
-            try_result!(set_within_map(&mut self.globals, context, sym, item));
-        Ok(previous)
+        self.globals.define(sym, item);
+        Ok(item.as_value())
     }
 
+    /// binds `sym` in the current frame
     pub fn set_local(
         &mut self,
-        context: UniquePointer<Context<'c>>,
+        _context: Pointer<Context<'c>>,
         sym: &Symbol<'c>,
         item: &Sym<'c>,
     ) -> Result<Value<'c>> {
-        let previous =
-            try_result!(set_within_map(&mut self.locals, context, sym, item));
-        Ok(previous)
+        self.environment.define(sym, item);
+        Ok(item.as_value())
     }
 
-    pub fn set_function_local(
+    /// updates the nearest binding of `sym`, defining it globally
+    /// when it is not bound at all
+    pub fn setq(
         &mut self,
-        context: UniquePointer<Context<'c>>,
+        _context: Pointer<Context<'c>>,
         sym: &Symbol<'c>,
         item: &Sym<'c>,
     ) -> Result<Value<'c>> {
-        let previous =
-            try_result!(set_within_map(&mut self.function_locals, context, sym, item));
-        Ok(previous)
+        if !self.environment.set(sym, item) {
+            self.globals.define(sym, item);
+        }
+        Ok(item.as_value())
     }
 
+    /// looks `sym` up through the environment chain, failing with
+    /// [`ErrorType::UnboundVariable`](crate::ErrorType) when it is not
+    /// bound unless it is a keyword or symbols are self-evaluating
     pub fn get(
         &mut self,
-        _vm: UniquePointer<Context<'c>>,
+        vm: Pointer<Context<'c>>,
         sym: &Symbol<'c>,
     ) -> Result<Sym<'c>> {
-        if let Some(value) = self
-            .function_locals
-            .get(sym)
-            .map(Clone::clone)
-            .or_else(|| self.locals.get(sym).map(Clone::clone))
-            .or_else(|| self.globals.get(sym).map(Clone::clone))
-        {
-            return Ok(value);
-        } else {
-            self.locals
-                .insert(sym.clone(), Sym::Value(sym.as_value()));
-            return Ok(Sym::Value(sym.as_value()));
+        match self.lookup(sym) {
+            Some(item) => Ok(item),
+            None if self.self_evaluating_symbols || sym.is_keyword() => Ok(Sym::Value(sym.as_value())),
+            None => Err(unbound_variable(sym, vm.inner_ref().locate(sym))),
         }
     }
+
+    pub fn lookup(&self, sym: &Symbol<'c>) -> Option<Sym<'c>> {
+        self.environment.get(sym)
+    }
+
+    /// the property list of `sym`, nil until a property is put
+    pub fn plist(&self, sym: &Symbol<'c>) -> Value<'c> {
+        self.plists.borrow().get(sym).cloned().unwrap_or_default()
+    }
+
+    pub fn set_plist(&self, sym: &Symbol<'c>, plist: Value<'c>) {
+        self.plists.borrow_mut().insert(sym.clone(), plist);
+    }
+
+    /// the location `form` was read from, when it is a list read
+    /// by [`Context::eval_source`]
+    pub fn location(&self, form: &Value<'c>) -> Option<Location> {
+        self.locations.borrow().get(form).cloned()
+    }
+
+    pub fn is_located(&self, form: &Value<'c>) -> bool {
+        self.locations.borrow().contains(form)
+    }
+
+    pub fn add_locations(&self, locations: Locations<'c>) {
+        self.locations.borrow_mut().extend(locations);
+    }
+
+    /// when enabled, unbound symbols evaluate to themselves instead
+    /// of failing with an unbound variable or function error
+    pub fn set_self_evaluating_symbols(&mut self, enabled: bool) {
+        self.self_evaluating_symbols = enabled;
+    }
 }
 
 fn register_builtin_function<'c>(
@@ -150,19 +354,3 @@ fn register_builtin_function<'c>(
     });
     table.insert(Symbol::new(sym), function.clone());
 }
-
-fn set_within_map<'c>(
-    map: &mut SymTable<'c>,
-    _context_: UniquePointer<Context<'c>>,
-    sym: &Symbol<'c>,
-    item: &Sym<'c>,
-) -> Result<Value<'c>> {
-    let previous = map.insert(sym.clone(), item.clone());
-
-    Ok(match previous.unwrap_or_else(|| item.clone()) {
-        Sym::Value(_) => item.clone(),
-        Sym::Function(Function::Defun { .. }) => item.clone(),
-        Sym::Function(Function::Builtin { .. }) => item.clone(),
-    }
-    .as_value())
-}
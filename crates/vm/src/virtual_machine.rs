@@ -1,19 +1,23 @@
 use std::collections::VecDeque;
 use std::fmt::Debug;
 
+use iocore::Path;
 use sexprs_data_structures::{Symbol, Value};
 use sexprs_util::try_result;
-use unique_pointer::UniquePointer;
 
 use crate::{
-    Context, Result, Sym, SymTable,
+    Backtrace, Condition, Context, Error, Pointer, Result, Sym, SymTable,
     SymbolTable,
 };
 
 #[derive(Clone)]
 pub struct VirtualMachine<'c> {
     pub symbols: SymbolTable<'c>,
-    stack: VecDeque<UniquePointer<Context<'c>>>,
+    stack: VecDeque<Box<Context<'c>>>,
+    /// the context of the last evaluation, kept after it is popped
+    /// for [`VirtualMachine::condition`] and
+    /// [`VirtualMachine::backtrace`]
+    last: Option<Box<Context<'c>>>,
 }
 
 impl<'c> Debug for VirtualMachine<'c> {
@@ -30,53 +34,140 @@ impl<'c> Debug for VirtualMachine<'c> {
     }
 }
 
+/// the lisp source of the functions and macros defined by
+/// [`VirtualMachine::new`] on top of the builtins
+pub const PRELUDE: &str = include_str!("prelude.lisp");
+
 impl<'c> VirtualMachine<'c> {
+    /// a virtual machine with the builtins and the definitions of the
+    /// standard [`PRELUDE`]
     pub fn new() -> VirtualMachine<'c> {
-        let vm = VirtualMachine {
+        let mut vm = VirtualMachine::bare();
+        vm.eval_prelude(PRELUDE, Some("prelude.lisp")).expect("the standard prelude to evaluate");
+        vm
+    }
+
+    /// a virtual machine with the builtins only
+    pub fn bare() -> VirtualMachine<'c> {
+        VirtualMachine {
             symbols: SymbolTable::new(),
             stack: VecDeque::new(),
-        };
-        vm
+            last: None,
+        }
+    }
+
+    /// a virtual machine with the builtins, the definitions of the
+    /// standard [`PRELUDE`] and then those of `prelude`, which may
+    /// redefine them. Errors are located within `filename`.
+    pub fn with_prelude(prelude: &str, filename: Option<&str>) -> Result<VirtualMachine<'c>> {
+        let mut vm = VirtualMachine::new();
+        try_result!(vm.eval_prelude(prelude, filename));
+        Ok(vm)
+    }
+
+    /// a virtual machine with the builtins and the definitions of
+    /// `prelude` instead of the standard [`PRELUDE`]. Errors are
+    /// located within `filename`.
+    pub fn with_custom_prelude(
+        prelude: &str,
+        filename: Option<&str>,
+    ) -> Result<VirtualMachine<'c>> {
+        let mut vm = VirtualMachine::bare();
+        try_result!(vm.eval_prelude(prelude, filename));
+        Ok(vm)
+    }
+
+    /// evaluates `prelude` in a context that is dropped afterwards,
+    /// as the context refers to the virtual machine at its current
+    /// address, which changes when a constructor returns it
+    fn eval_prelude(&mut self, prelude: &str, filename: Option<&str>) -> Result<()> {
+        let result = self.push_context().eval_source(prelude, filename);
+        self.stack.pop_front();
+        try_result!(result);
+        Ok(())
     }
 
     pub fn setq(&mut self, symbol: Symbol<'c>, value: Value<'c>) -> Result<Value<'c>> {
-        let context = self.push_context();
-        let previous = try_result!(self.symbols.set_global(context, &symbol, &Sym::Value(value)));
-        self.update_symbols();
+        let previous = try_result!(self.with_context(|context, symbols| {
+            symbols.set_global(context, &symbol, &Sym::Value(value))
+        }));
         Ok(previous)
     }
+    /// opts into the historical behaviour of unbound symbols
+    /// evaluating to themselves instead of signalling
+    /// `UnboundVariable` or `UnboundFunction`
+    pub fn set_self_evaluating_symbols(&mut self, enabled: bool) {
+        self.symbols.set_self_evaluating_symbols(enabled);
+    }
+
+    /// the lisp condition of an error returned by the last evaluation,
+    /// including the data of conditions signalled by lisp code
+    pub fn condition(&self, error: &Error) -> Condition<'c> {
+        match self.last_context() {
+            Some(context) => context.condition_of(error),
+            None => Condition::from_error(error),
+        }
+    }
+
+    /// the lisp call stack at the point the error returned by the last
+    /// evaluation was raised, innermost call first
+    pub fn backtrace(&self) -> Backtrace<'c> {
+        match self.last_context() {
+            Some(context) => context.backtrace(),
+            None => Backtrace::default(),
+        }
+    }
+
     pub fn symbols(&self) -> SymTable<'c> {
-        self.symbols.locals.clone()
+        self.symbols.globals()
     }
 
-    pub(crate) fn push_context(&mut self) -> UniquePointer<Context<'c>> {
-        let context = UniquePointer::<Context<'c>>::from(Context::new(
-            UniquePointer::read_only(self),
+    pub(crate) fn push_context(&mut self) -> Pointer<Context<'c>> {
+        let context = Box::new(Context::new(
+            Pointer::read_only(self),
             self.symbols.clone(),
         ));
-        self.stack.push_front(context.clone());
-        context
+        self.stack.push_front(context);
+        Pointer::read_only(&self.stack[0])
     }
 
-    pub(crate) fn last_context(&self) -> Option<&UniquePointer<Context<'c>>> {
-        self.stack.front()
+    /// calls `eval` with a new context, which is popped once it
+    /// returns and kept as the last one
+    fn with_context<T>(
+        &mut self,
+        eval: impl FnOnce(Pointer<Context<'c>>, &mut SymbolTable<'c>) -> Result<T>,
+    ) -> Result<T> {
+        let context = self.push_context();
+        let result = eval(context, &mut self.symbols);
+        self.last = self.stack.pop_front();
+        result
     }
 
-    pub(crate) fn update_symbols(&mut self) {
-        if let Some(context) = self.last_context() {
-            self.symbols.extend(context.symbols.clone());
-        }
+    pub(crate) fn last_context(&self) -> Option<&Context<'c>> {
+        self.stack.front().or(self.last.as_ref()).map(AsRef::as_ref)
+    }
+
+    pub fn eval_string(&mut self, string: &str) -> Result<Value<'c>> {
+        let value = try_result!(self.with_context(|mut context, _| context.eval_string(string)));
+        Ok(value)
+    }
+
+    pub fn eval_program(&mut self, string: &str) -> Result<Value<'c>> {
+        let value = try_result!(self.with_context(|mut context, _| context.eval_program(string)));
+        Ok(value)
     }
 
-    pub fn eval_string(&mut self, string: &'c str) -> Result<Value<'c>> {
-        let value = try_result!(self.push_context().eval_string(string));
-        self.update_symbols();
+    pub fn eval_file<T: std::fmt::Display>(&mut self, path: T) -> Result<Value<'c>> {
+        let filename = path.to_string();
+        let source = try_result!(Path::new(&filename).read());
+        let value = try_result!(self.with_context(|mut context, _| {
+            context.eval_source(&source, Some(&filename))
+        }));
         Ok(value)
     }
 
     pub fn eval(&mut self, item: Value<'c>) -> Result<Value<'c>> {
-        let value = try_result!(self.push_context().eval(item));
-        self.update_symbols();
+        let value = try_result!(self.with_context(|mut context, _| context.eval(item)));
         Ok(value)
     }
 
@@ -85,10 +176,9 @@ impl<'c> VirtualMachine<'c> {
         sym: &Symbol<'c>,
         list: Value<'c>,
     ) -> Result<Value<'c>> {
-        let value = try_result!(self
-            .push_context()
-            .eval_symbol_function(sym, list));
-        self.update_symbols();
+        let value = try_result!(self.with_context(|mut context, _| {
+            context.eval_symbol_function(sym, list)
+        }));
         Ok(value)
     }
 }
@@ -0,0 +1,48 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_assoc_and_assq() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(r#"(setq config '(("host" . "localhost") (port . 8080) ignored (debug t)))"#)?;
+    assert_equal!(vm.eval_string(r#"(assoc "host" config)"#)?.to_string(), r#"("host" . "localhost")"#);
+    assert_equal!(vm.eval_string("(assoc 'debug config)")?.to_string(), "(debug t)");
+    assert_equal!(vm.eval_string("(cdr (assq 'port config))")?, Value::unsigned_integer(8080u32));
+    assert_equal!(vm.eval_string(r#"(assq "host" config)"#)?, Value::Nil);
+    assert_equal!(vm.eval_string("(assoc 'missing config)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(assoc 'a nil)")?, Value::Nil);
+    Ok(())
+}
+
+#[test]
+fn test_assoc_with_a_test_function() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(defun close-to (a b) (< (abs (- a b)) 2))")?;
+    assert_equal!(vm.eval_string("(assoc 10 '((1 . a) (9 . b)) 'close-to)")?.to_string(), "(9 . b)");
+    assert_equal!(
+        vm.eval_string("(alist-get 10 '((1 . a) (11 . c)) nil nil (lambda (a b) (= a (- b 1))))")?,
+        Value::Nil
+    );
+    assert_equal!(
+        vm.eval_string("(alist-get 10 '((1 . a) (11 . c)) nil nil (lambda (a b) (= a (+ b 1))))")?
+            .to_string(),
+        "c"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_rassoc_and_alist_get() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(r##"(setq colors '((red . "#f00") (green . "#0f0")))"##)?;
+    assert_equal!(vm.eval_string(r##"(rassoc "#0f0" colors)"##)?.to_string(), r##"(green . "#0f0")"##);
+    assert_equal!(vm.eval_string("(alist-get 'red colors)")?, Value::from("#f00"));
+    assert_equal!(vm.eval_string("(alist-get 'blue colors)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(alist-get 'blue colors \"#00f\")")?, Value::from("#00f"));
+    let error = vm.eval_string("(alist-get 'red)").expect_err("arity");
+    assert_equal!(error.message().contains("alist-get takes 2 to 5 arguments, got: 1"), true);
+    Ok(())
+}
@@ -0,0 +1,142 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{ErrorType, Result, VirtualMachine};
+
+#[test]
+fn test_backtrace_lists_the_calls_innermost_first() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        "(defun inner (x) (error \"boom\" x))
+(defun outer (x) (+ 1 (inner x)))",
+    )?;
+    let error = vm.eval_string("(outer 2)").expect_err("inner signals");
+    assert_equal!(error.ty(), ErrorType::RuntimeError);
+    let backtrace = vm.backtrace();
+    assert_equal!(backtrace.len(), 2);
+    assert_equal!(backtrace.frames[0].name.to_string(), "inner");
+    assert_equal!(backtrace.frames[0].args.to_string(), "(2)");
+    assert_equal!(backtrace.frames[1].name.to_string(), "outer");
+    assert_equal!(
+        backtrace.to_string(),
+        "in inner at line 2, column 23 ← called from outer at line 1, column 1"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_backtrace_locates_repeated_calls_by_their_arguments() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        "(defun check (x)
+  (if (null x) (error \"no value\") x))
+(defun both (a b)
+  (list (check a)
+        (check b)))",
+    )?;
+    vm.eval_string("(both 1 nil)").expect_err("b is nil");
+    assert_equal!(
+        vm.backtrace().to_string(),
+        "in check at line 5, column 9 ← called from both at line 1, column 1"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_backtrace_locates_calls_that_print_alike() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        "(defun check (x)
+  (if (> x 2) (error \"too big\" x) x))
+(defun run ()
+  (check 1)
+  (check 3.0)
+  t)",
+    )?;
+    vm.eval_string("(run)").expect_err("3.0 is too big");
+    assert_equal!(
+        vm.backtrace().to_string(),
+        "in check at line 5, column 3 ← called from run at line 1, column 1"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_backtrace_locates_calls_around_comments() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        "(defun check (x) (error \"no\" x))
+(defun run ()
+  (list 1 ; one
+        (check ; the failing call
+         2)))",
+    )?;
+    vm.eval_string("(run)").expect_err("check signals");
+    assert_equal!(
+        vm.backtrace().to_string(),
+        "in check at line 4, column 9 ← called from run at line 1, column 1"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_tail_calls_keep_the_frame_they_started_from() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        "(defun fail (xs) (error \"failed at\" xs))
+(defun walk (xs) (if (null (cdr xs)) (fail xs) (walk (cdr xs))))",
+    )?;
+    vm.eval_string("(walk '(1 2 3 4 5))").expect_err("fail signals");
+    let backtrace = vm.backtrace();
+    assert_equal!(backtrace.len(), 2);
+    assert_equal!(
+        backtrace.to_string(),
+        "in fail at line 2, column 38 ← called from walk at line 1, column 1"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_handled_errors_leave_no_backtrace() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(defun boom () (error \"boom\"))")?;
+    assert_equal!(
+        vm.eval_string("(condition-case nil (boom) (error 'handled))")?,
+        Value::quoted_symbol("handled")
+    );
+    assert_equal!(vm.backtrace().is_empty(), true);
+    Ok(())
+}
+
+#[test]
+fn test_funcall_frames_have_no_call_site() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(defun boom (x) (error \"boom\" x))")?;
+    vm.eval_string("(funcall 'boom 1)").expect_err("boom signals");
+    assert_equal!(vm.backtrace().to_string(), "in boom");
+    Ok(())
+}
+
+#[test]
+fn test_backtrace_names_the_file() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("sexprs-backtrace-{}.lisp", std::process::id()));
+    std::fs::write(
+        &path,
+        "(defun flatten (x)\n  (error \"not a list\" x))\n\n(defun run ()\n  (list 1\n        (flatten 2)))\n(run)\n",
+    )
+    .expect("write temporary file");
+    let mut vm = VirtualMachine::new();
+    let result = vm.eval_file(path.display());
+    std::fs::remove_file(&path).expect("remove temporary file");
+    result.expect_err("flatten signals");
+    assert_equal!(
+        vm.backtrace().to_string(),
+        format!(
+            "in flatten at {}:6:9 ← called from run at {}:7:1",
+            path.display(),
+            path.display()
+        )
+    );
+    Ok(())
+}
@@ -0,0 +1,89 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Symbol, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{ErrorType, Result, VirtualMachine};
+
+#[test]
+fn test_let_binds_in_parallel_and_does_not_leak() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(setq x 1)
+(let ((x 10) (y x) z) (list x y (null z)))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "(10 1 t)");
+    assert_equal!(vm.symbols().contains_key(&Symbol::new("y")), false);
+    assert_equal!(vm.eval_string("x")?, Value::unsigned_integer(1u32));
+    Ok(())
+}
+
+#[test]
+fn test_let_star_binds_sequentially() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(let* ((x 1) (y (+ x 1)) (x (* y 10))) (list x y))")?
+            .to_string(),
+        "(20 2)"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_letrec_allows_recursive_lambdas() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(letrec ((len (lambda (l) (if (null l) 0 (+ 1 (funcall len (cdr l)))))))
+  (funcall len '(a b c)))
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(3u32));
+    Ok(())
+}
+
+#[test]
+fn test_flet_functions_see_the_enclosing_scope() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun double (n) (* n 2))
+(let ((offset 1))
+  (flet ((double (n) (+ (double n) offset)))
+    (double 5)))
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(11u32));
+    assert_equal!(vm.eval_string("(double 5)")?, Value::unsigned_integer(10u32));
+    Ok(())
+}
+
+#[test]
+fn test_labels_functions_can_recurse() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(labels ((even (n) (if (null n) t (odd (cdr n))))
+         (odd (n) (if (null n) nil (even (cdr n)))))
+  (list (even '(1 2)) (odd '(1 2 3))))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "(t t)");
+    Ok(())
+}
+
+#[test]
+fn test_let_destructures_lists() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(let (((a (b c) &rest d) '(1 (2 3) 4 5))) (list a b c d))")?
+            .to_string(),
+        "(1 2 3 (4 5))"
+    );
+    let error = vm
+        .eval_string("(let (((a b) '(1))) a)")
+        .expect_err("not enough values");
+    assert_equal!(error.message().contains("not enough values to destructure"), true);
+    Ok(())
+}
@@ -0,0 +1,104 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_lambda_called_directly() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("((lambda (x y) (+ x y)) 1 2)")?,
+        Value::unsigned_integer(3u32)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_funcall_with_lambda_and_builtin() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(funcall (lambda (x) (* x 2)) 21)")?,
+        Value::unsigned_integer(42u32)
+    );
+    assert_equal!(
+        vm.eval_string("(funcall 'car '(a b))")?.to_string(),
+        "a"
+    );
+    assert_equal!(
+        vm.eval_string("(funcall 'cons 1 (list 2 3))")?.to_string(),
+        "(1 2 3)"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_apply_spreads_the_last_argument() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(apply '+ 1 2 '(3 4))")?,
+        Value::unsigned_integer(10u32)
+    );
+    assert_equal!(
+        vm.eval_string("(apply (lambda (&rest xs) xs) '(a b))")?.to_string(),
+        "(a b)"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_closure_returned_from_defun_keeps_its_bindings() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun make-adder (n) (lambda (x) (+ x n)))
+(setq add2 (make-adder 2))
+(setq add10 (make-adder 10))
+(list (funcall add2 1) (funcall add10 1))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "(3 11)");
+    Ok(())
+}
+
+#[test]
+fn test_closures_are_first_class_arguments() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun twice (f x) (funcall f (funcall f x)))
+(twice (lambda (n) (* n 3)) 2)
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(18u32));
+    Ok(())
+}
+
+#[test]
+fn test_funcall_of_non_function_is_an_error() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let error = vm.eval_string("(funcall 1 2)").expect_err("1 is not a function");
+    assert_equal!(error.message().contains("invalid function: 1"), true);
+    Ok(())
+}
+
+#[test]
+fn test_lambda_returns_a_procedure_not_a_symbol() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let globals = vm.symbols().len();
+    let value = vm.eval_string("(lambda (x) (* x 2))")?;
+    assert_equal!(matches!(value, Value::Procedure(_)), true);
+    assert_equal!(value.to_string(), "(lambda (x) (* x 2))");
+    vm.eval_string("(list (lambda () 1) (lambda () 2))")?;
+    assert_equal!(vm.symbols().len(), globals);
+    assert_equal!(vm.eval_string("(symbol-name (lambda () 1))").is_err(), true);
+    Ok(())
+}
+
+#[test]
+fn test_procedures_are_eq_to_themselves_only() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(let ((f (lambda () 1))) (eq f f))")?, Value::T);
+    assert_equal!(vm.eval_string("(eq (lambda () 1) (lambda () 1))")?, Value::nil());
+    Ok(())
+}
@@ -0,0 +1,44 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::Value;
+use sexprs_util::Traceback;
+use sexprs_vm::{ErrorType, Result, VirtualMachine};
+
+#[test]
+fn test_numeric_equality_ignores_types() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(= 1 1.0 2/2)")?, Value::T);
+    assert_equal!(vm.eval_string("(= 1 -1)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(= 123456789012345678901234567890 123456789012345678901234567890)")?, Value::T);
+    assert_equal!(vm.eval_string("(/= 1 2 3)")?, Value::T);
+    assert_equal!(vm.eval_string("(/= 1 2 1.0)")?, Value::Nil);
+    Ok(())
+}
+
+#[test]
+fn test_ordering_chains() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(< -1 0 1/2 1 1.5 123456789012345678901234567890)")?, Value::T);
+    assert_equal!(vm.eval_string("(< 1 1)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(<= 1 1 2)")?, Value::T);
+    assert_equal!(vm.eval_string("(> 3 2.5 -7/3)")?, Value::T);
+    assert_equal!(vm.eval_string("(>= 3 3 4)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(< 1)")?, Value::T);
+    Ok(())
+}
+
+#[test]
+fn test_min_and_max() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(min 3 -2 1/2)")?, Value::integer(-2i64));
+    assert_equal!(vm.eval_string("(max 3 7/2 1.5)")?, Value::ratio(7, 2));
+    Ok(())
+}
+
+#[test]
+fn test_comparison_rejects_non_numbers() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let error = vm.eval_string("(< 1 \"two\")").expect_err("strings are not numbers");
+    assert_equal!(error.message().ends_with("< called with non-numerical value: \"two\""), true);
+    Ok(())
+}
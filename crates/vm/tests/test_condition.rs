@@ -0,0 +1,152 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Symbol, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Condition, ErrorType, Result, VirtualMachine};
+
+#[test]
+fn test_condition_case_handles_signalled_conditions() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(condition-case err
+    (signal 'my-error '(1 2))
+  (other-error 'other)
+  (my-error (cdr err)))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "(1 2)");
+    Ok(())
+}
+
+#[test]
+fn test_condition_case_handles_native_errors() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(condition-case err (undefined-variable) (void-function (car err)))")?
+            .to_string(),
+        "void-function"
+    );
+    assert_equal!(
+        vm.eval_string(r#"(condition-case nil (error "boom") (error 'handled))"#)?
+            .to_string(),
+        "'handled"
+    );
+    assert_equal!(
+        vm.eval_string("(condition-case nil (+ 1 2) (error 'handled))")?,
+        Value::unsigned_integer(3u32)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unhandled_conditions_propagate() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let error = vm
+        .eval_string("(condition-case nil (signal 'my-error '(1 2)) (other-error 'other))")
+        .expect_err("my-error is not handled");
+    assert_equal!(error.ty(), ErrorType::Signal);
+    let condition = vm.condition(&error);
+    assert_equal!(condition.symbol, Symbol::new("my-error"));
+    assert_equal!(condition.data.to_string(), "(1 2)");
+    Ok(())
+}
+
+#[test]
+fn test_error_type_maps_to_condition_symbols() -> Result<()> {
+    assert_equal!(ErrorType::UnboundVariable.condition_symbol(), "void-variable");
+    assert_equal!(ErrorType::from_condition_symbol("void-variable"), ErrorType::UnboundVariable);
+    assert_equal!(ErrorType::from_condition_symbol("error"), ErrorType::RuntimeError);
+    assert_equal!(ErrorType::from_condition_symbol("my-error"), ErrorType::Signal);
+    let mut vm = VirtualMachine::new();
+    let error = vm.eval_string(r#"(error "boom")"#).expect_err("error signals");
+    assert_equal!(error.ty(), ErrorType::RuntimeError);
+    assert_equal!(error.message().ends_with("boom"), true);
+    Ok(())
+}
+
+#[test]
+fn test_unwind_protect_runs_cleanup_on_success_and_error() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(setq cleaned nil) (unwind-protect 1 (setq cleaned t))")?,
+        Value::unsigned_integer(1u32)
+    );
+    assert_equal!(vm.eval_string("cleaned")?, Value::T);
+    let value = vm.eval_string(
+        r#"
+(setq cleaned nil)
+(condition-case nil
+    (unwind-protect (signal 'my-error nil) (setq cleaned t))
+  (my-error cleaned))
+"#,
+    )?;
+    assert_equal!(value, Value::T);
+    Ok(())
+}
+
+#[test]
+fn test_ignore_errors() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(ignore-errors (undefined) 1)")?, Value::nil());
+    assert_equal!(vm.eval_string("(ignore-errors 1 2)")?, Value::unsigned_integer(2u32));
+    Ok(())
+}
+
+#[test]
+fn test_catch_and_throw() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun find-first-list (items)
+  (catch 'found
+    (dolist (item items)
+      (when (listp item) (throw 'found item)))
+    nil))
+(find-first-list '(1 2 (3 4) 5))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "(3 4)");
+    assert_equal!(
+        vm.eval_string("(catch 'outer (catch 'inner (throw 'outer 1)) 2)")?,
+        Value::unsigned_integer(1u32)
+    );
+    let error = vm.eval_string("(throw 'nowhere 1)").expect_err("no catch");
+    assert_equal!(error.ty(), ErrorType::NoCatch);
+    Ok(())
+}
+
+#[test]
+fn test_error_formats_its_message() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string(r#"(condition-case err (error "bad %s: %d" 'thing 3) (error (cdr err)))"#)?
+            .to_string(),
+        r#"("bad thing: 3")"#
+    );
+    let error = vm.eval_string(r#"(error "bad %s" 'thing)"#).expect_err("error signals");
+    assert_equal!(error.message().ends_with("bad thing"), true);
+    assert_equal!(vm.condition(&error).data.to_string(), r#"("bad thing")"#);
+    Ok(())
+}
+
+#[test]
+fn test_errors_carry_the_condition_they_were_signalled_with() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(condition-case err
+    (unwind-protect
+        (signal 'my-error '(1 2))
+      (condition-case nil (signal 'other-error '(3)) (other-error nil)))
+  (my-error (cdr err)))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "(1 2)");
+    let error = vm
+        .eval_string(r#"(progn (ignore-errors (error "boom")) (+ 1 "boom"))"#)
+        .expect_err("+ takes numbers");
+    assert_equal!(error.condition(), None);
+    assert_equal!(vm.condition(&error).symbol, Symbol::new("error"));
+    Ok(())
+}
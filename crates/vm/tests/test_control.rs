@@ -0,0 +1,149 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_if_evaluates_the_branch_once() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(if t (list 1 2) 3)")?.to_string(), "(1 2)");
+    assert_equal!(vm.eval_string("(if nil 1 (setq x 2) (list x 3))")?.to_string(), "(2 3)");
+    assert_equal!(vm.eval_string("(if nil 1)")?, Value::nil());
+    Ok(())
+}
+
+#[test]
+fn test_cond() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        r#"
+(defun classify (n)
+  (cond ((null n) 'empty)
+        ((listp n) 'list)
+        (t 'other)))
+"#,
+    )?;
+    assert_equal!(vm.eval_string("(classify nil)")?.to_string(), "'empty");
+    assert_equal!(vm.eval_string("(classify '(1))")?.to_string(), "'list");
+    assert_equal!(vm.eval_string("(classify 1)")?.to_string(), "'other");
+    assert_equal!(vm.eval_string("(cond (nil 1) (2))")?, Value::unsigned_integer(2u32));
+    assert_equal!(vm.eval_string("(cond (nil 1))")?, Value::nil());
+    Ok(())
+}
+
+#[test]
+fn test_when_and_unless() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(when t 1 2)")?, Value::unsigned_integer(2u32));
+    assert_equal!(vm.eval_string("(when nil (undefined))")?, Value::nil());
+    assert_equal!(vm.eval_string("(unless nil 1 2)")?, Value::unsigned_integer(2u32));
+    assert_equal!(vm.eval_string("(unless t (undefined))")?, Value::nil());
+    Ok(())
+}
+
+#[test]
+fn test_and_or_short_circuit() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(and)")?, Value::T);
+    assert_equal!(vm.eval_string("(and 1 2 3)")?, Value::unsigned_integer(3u32));
+    assert_equal!(vm.eval_string("(and 1 nil (undefined))")?, Value::nil());
+    assert_equal!(vm.eval_string("(or)")?, Value::nil());
+    assert_equal!(vm.eval_string("(or nil 2 (undefined))")?, Value::unsigned_integer(2u32));
+    Ok(())
+}
+
+#[test]
+fn test_progn_and_prog1() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(progn (setq a 1) (+ a 1))")?, Value::unsigned_integer(2u32));
+    assert_equal!(vm.eval_string("(prog1 a (setq a 5))")?, Value::unsigned_integer(1u32));
+    assert_equal!(vm.eval_string("a")?, Value::unsigned_integer(5u32));
+    Ok(())
+}
+
+#[test]
+fn test_case() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        r#"
+(defun kind (x)
+  (case x
+    ((1 2 3) 'small)
+    (apple 'fruit)
+    (otherwise 'unknown)))
+"#,
+    )?;
+    assert_equal!(vm.eval_string("(kind 2)")?.to_string(), "'small");
+    assert_equal!(vm.eval_string("(kind 'apple)")?.to_string(), "'fruit");
+    assert_equal!(vm.eval_string("(kind 9)")?.to_string(), "'unknown");
+    Ok(())
+}
+
+#[test]
+fn test_case_compares_keys_with_eql() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        r#"
+(defun kind (x)
+  (case x
+    (nil 'bare-nil)
+    (() 'empty)
+    (("a" (1 2)) 'structural)
+    ((1.5 3/4) 'number)
+    (otherwise 'unknown)))
+"#,
+    )?;
+    assert_equal!(vm.eval_string("(kind nil)")?.to_string(), "'unknown");
+    assert_equal!(vm.eval_string(r#"(kind "a")"#)?.to_string(), "'unknown");
+    assert_equal!(vm.eval_string("(kind '(1 2))")?.to_string(), "'unknown");
+    assert_equal!(vm.eval_string("(kind 1.5)")?.to_string(), "'number");
+    assert_equal!(vm.eval_string("(kind (/ 3 4))")?.to_string(), "'number");
+    Ok(())
+}
+
+#[test]
+fn test_while() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(setq items '(1 2 3))
+(setq total 0)
+(while items
+  (setq total (+ total (car items)))
+  (setq items (cdr items)))
+total
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(6u32));
+    Ok(())
+}
+
+#[test]
+fn test_dolist_and_dotimes() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(let ((sum 0)) (dolist (x '(1 2 3) sum) (setq sum (+ sum x))))")?,
+        Value::unsigned_integer(6u32)
+    );
+    assert_equal!(
+        vm.eval_string("(let ((sum 0)) (dotimes (i 4) (setq sum (+ sum i))) sum)")?,
+        Value::unsigned_integer(6u32)
+    );
+    assert_equal!(vm.eval_string("(dotimes (i 3 i))")?, Value::unsigned_integer(3u32));
+    Ok(())
+}
+
+#[test]
+fn test_closures_made_in_a_loop_keep_their_own_binding() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(setq closures nil)
+(dotimes (i 3) (setq closures (cons (lambda () i) closures)))
+(list (funcall (car closures)) (funcall (car (cdr closures))))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "(2 1)");
+    Ok(())
+}
@@ -14,7 +14,7 @@ fn test_eval_if_then() -> Result<()> {
       \"false\")
 ",
     )?;
-    assert_equal!(value.to_string(), "(\"true\")");
+    assert_equal!(value.to_string(), "\"true\"");
     Ok(())
 }
 
@@ -25,7 +25,7 @@ fn test_eval_defun_recursive_flatten() -> Result<()> {
         "
 (defun flatten (lst)
   (if (null lst)
-      T
+      nil
     (if (listp (car lst))
         (append
          (flatten (car lst))
@@ -56,6 +56,15 @@ fn test_eval_setq() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_eval_setq_evaluates_each_value() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let val = vm.eval_string(r#"(setq a (+ 1 2) b (list a 4))"#)?;
+    assert_equal!(val.to_string(), "(3 4)");
+    assert_equal!(vm.eval_string("(+ a 1)")?, Value::unsigned_integer(4u64));
+    Ok(())
+}
+
 #[test]
 fn test_eval_defun() -> Result<()> {
     let mut vm = VirtualMachine::new();
@@ -84,7 +93,7 @@ fn test_eval_if_else() -> Result<()> {
       \"false\")
 ",
     )?;
-    assert_equal!(value.to_string(), "(\"false\")");
+    assert_equal!(value.to_string(), "\"false\"");
     Ok(())
 }
 
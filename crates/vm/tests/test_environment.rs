@@ -0,0 +1,73 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Symbol, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_parameters_do_not_leak_into_the_caller() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(defun identity-of (secret) secret)")?;
+    vm.eval_string("(identity-of 1)")?;
+    assert_equal!(vm.symbols().contains_key(&Symbol::new("secret")), false);
+    Ok(())
+}
+
+#[test]
+fn test_parameters_shadow_globals() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(setq x 1)
+(defun shadow (x) (setq x (+ x 10)) x)
+(list (shadow 5) x)
+"#,
+    )?;
+    assert_equal!(value.to_string(), "(15 1)");
+    Ok(())
+}
+
+#[test]
+fn test_recursive_calls_keep_their_own_bindings() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun sum-to (n) (if (null n) 0 (+ (car n) (sum-to (cdr n)))))
+(sum-to '(1 2 3 4))
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(10u32));
+    Ok(())
+}
+
+#[test]
+fn test_functions_do_not_see_the_callers_bindings() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(setq y 'global)
+(defun read-y () y)
+(defun call-with-y (y) (read-y))
+(call-with-y 'local)
+"#,
+    )?;
+    assert_equal!(value.to_string(), "'global");
+    Ok(())
+}
+
+#[test]
+fn test_setq_updates_the_binding_captured_by_a_closure() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun make-counter (count) (lambda () (setq count (+ count 1))))
+(setq counter (make-counter 0))
+(funcall counter)
+(funcall counter)
+(funcall counter)
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(3u32));
+    assert_equal!(vm.symbols().contains_key(&Symbol::new("count")), false);
+    Ok(())
+}
@@ -0,0 +1,50 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::Value;
+use sexprs_util::Traceback;
+use sexprs_vm::{ErrorType, Result, VirtualMachine};
+
+#[test]
+fn test_eq_compares_identity() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(eq 'a 'a)")?, Value::T);
+    assert_equal!(vm.eval_string("(eq 'a 'b)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(eq nil '())")?, Value::T);
+    assert_equal!(vm.eval_string("(eq 3 3)")?, Value::T);
+    assert_equal!(vm.eval_string("(eq 1.5 1.5)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(eq (list 1 2) (list 1 2))")?, Value::Nil);
+    assert_equal!(vm.eval_string("(let ((x (list 1 2))) (eq x x))")?, Value::T);
+    assert_equal!(vm.eval_string("(let* ((x (list 1 2)) (y x)) (eq x y))")?, Value::T);
+    Ok(())
+}
+
+#[test]
+fn test_eql_compares_numbers_of_the_same_type() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(eql 1.5 1.5)")?, Value::T);
+    assert_equal!(vm.eval_string("(eql 1/2 2/4)")?, Value::T);
+    assert_equal!(vm.eval_string("(eql 123456789012345678901234567890 123456789012345678901234567890)")?, Value::T);
+    assert_equal!(vm.eval_string("(eql 1 1.0)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(eql 0.0 -0.0)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(eql (list 1) (list 1))")?, Value::Nil);
+    Ok(())
+}
+
+#[test]
+fn test_equal_compares_structure() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(equal (cons 1 '((2 \"three\"))) '(1 (2 \"three\")))")?, Value::T);
+    assert_equal!(vm.eval_string("(equal (list 1 2) (list 1 2 3))")?, Value::Nil);
+    assert_equal!(vm.eval_string("(equal \"abc\" \"abc\")")?, Value::T);
+    assert_equal!(vm.eval_string("(equal 1 1.0)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(equal nil '())")?, Value::T);
+    Ok(())
+}
+
+#[test]
+fn test_equality_takes_two_arguments() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let error = vm.eval_string("(equal 1)").expect_err("equal compares two values");
+    assert_equal!(error.message().ends_with("equal takes 2 arguments, got: 1"), true);
+    Ok(())
+}
@@ -0,0 +1,92 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_puthash_gethash_and_remhash() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq table (make-hash-table))")?;
+    assert_equal!(vm.eval_string("(puthash 'a 1 table)")?, Value::unsigned_integer(1u32));
+    vm.eval_string("(puthash 'b 2 table)")?;
+    vm.eval_string("(puthash 'a 3 table)")?;
+    assert_equal!(vm.eval_string("(gethash 'a table)")?, Value::unsigned_integer(3u32));
+    assert_equal!(vm.eval_string("(gethash 'c table)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(gethash 'c table 'none)")?.to_string(), "'none");
+    assert_equal!(vm.eval_string("(hash-table-count table)")?, Value::unsigned_integer(2u32));
+    assert_equal!(vm.eval_string("(hash-table-keys table)")?.to_string(), "('a 'b)");
+    assert_equal!(vm.eval_string("(remhash 'a table)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(hash-table-keys table)")?.to_string(), "('b)");
+    assert_equal!(vm.eval_string("table")?.to_string(), "#s(hash-table test eql data ('b 2))");
+    Ok(())
+}
+
+#[test]
+fn test_make_hash_table_tests() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq equal-table (make-hash-table :test 'equal :size 10))")?;
+    vm.eval_string("(setq eql-table (make-hash-table :test 'eql))")?;
+    vm.eval_string("(puthash \"key\" 1 equal-table)")?;
+    vm.eval_string("(puthash \"key\" 1 eql-table)")?;
+    vm.eval_string("(puthash '(1 2) 'list equal-table)")?;
+    assert_equal!(vm.eval_string("(gethash \"key\" equal-table)")?, Value::unsigned_integer(1u32));
+    assert_equal!(vm.eval_string("(gethash \"key\" eql-table)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(gethash (list 1 2) equal-table)")?.to_string(), "'list");
+
+    vm.eval_string("(setq eq-table (make-hash-table :test 'eq))")?;
+    vm.eval_string("(setq key (list 1 2))")?;
+    vm.eval_string("(puthash key 'found eq-table)")?;
+    assert_equal!(vm.eval_string("(gethash key eq-table)")?.to_string(), "'found");
+    assert_equal!(vm.eval_string("(gethash (list 1 2) eq-table)")?, Value::Nil);
+
+    let error = vm.eval_string("(make-hash-table :test 'string=)").expect_err("invalid test");
+    assert_equal!(error.message().contains("make-hash-table called with invalid test: 'string="), true);
+    let error = vm.eval_string("(make-hash-table :weakness)").expect_err("missing value");
+    assert_equal!(error.message().contains("make-hash-table called without a value for: :weakness"), true);
+    let error = vm.eval_string("(make-hash-table :weakness 'key)").expect_err("unknown keyword");
+    assert_equal!(error.message().contains("make-hash-table called with unknown keyword: :weakness"), true);
+    let error = vm.eval_string("(gethash 'a '(a))").expect_err("not a table");
+    assert_equal!(error.message().contains("gethash called with non-hash-table value"), true);
+    Ok(())
+}
+
+#[test]
+fn test_maphash_visits_entries_in_insertion_order() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq table (make-hash-table :test 'equal))")?;
+    vm.eval_string("(puthash \"one\" 1 table)")?;
+    vm.eval_string("(puthash \"two\" 2 table)")?;
+    vm.eval_string("(setq total 0)")?;
+    vm.eval_string("(defun add-value (key value) (setq total (+ total value)))")?;
+    assert_equal!(vm.eval_string("(maphash 'add-value table)")?, Value::Nil);
+    assert_equal!(vm.eval_string("total")?, Value::unsigned_integer(3u32));
+    vm.eval_string("(setq keys nil)")?;
+    vm.eval_string("(maphash (lambda (key value) (setq keys (cons key keys))) table)")?;
+    assert_equal!(vm.eval_string("keys")?.to_string(), r#"("two" "one")"#);
+    Ok(())
+}
+
+#[test]
+fn test_hash_table_literals_round_trip() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq table #s(hash-table test equal data (\"a\" 1 b (2 3))))")?;
+    assert_equal!(vm.eval_string("(gethash \"a\" table)")?, Value::unsigned_integer(1u32));
+    assert_equal!(vm.eval_string("(gethash 'b table)")?.to_string(), "(2 3)");
+    assert_equal!(
+        vm.eval_string("table")?.to_string(),
+        r#"#s(hash-table test equal data ("a" 1 b (2 3)))"#
+    );
+    assert_equal!(vm.eval_string("(equal table table)")?, Value::T);
+    assert_equal!(vm.eval_string("(eq table (make-hash-table))")?, Value::Nil);
+    Ok(())
+}
+
+#[test]
+fn test_keywords_evaluate_to_themselves() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string(":test")?, Value::symbol(":test"));
+    vm.eval_string("(setq :bound 1)")?;
+    assert_equal!(vm.eval_string(":bound")?, Value::unsigned_integer(1u32));
+    Ok(())
+}
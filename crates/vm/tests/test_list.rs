@@ -6,6 +6,7 @@ use sexprs_vm::{Result, VirtualMachine};
 #[test]
 fn test_car() -> Result<()> {
     let mut vm = VirtualMachine::new();
+    vm.set_self_evaluating_symbols(true);
     let ast = list([
         Value::symbol("car"),
         list([
@@ -23,6 +24,7 @@ fn test_car() -> Result<()> {
 #[test]
 fn test_cdr() -> Result<()> {
     let mut vm = VirtualMachine::new();
+    vm.set_self_evaluating_symbols(true);
     let ast = list([
         Value::symbol("cdr"),
         list([
@@ -40,6 +42,7 @@ fn test_cdr() -> Result<()> {
 #[test]
 fn test_append() -> Result<()> {
     let mut vm = VirtualMachine::new();
+    vm.set_self_evaluating_symbols(true);
     let ast = list([
         Value::symbol("append"),
         list([Value::symbol("a")]),
@@ -57,3 +60,82 @@ fn test_append() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn test_append_keeps_the_last_argument_as_its_cdr() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(append '(1) (cons 2 3))")?.to_string(), "(1 2 . 3)");
+    assert_equal!(vm.eval_string("(append '(1 2) 3)")?.to_string(), "(1 2 . 3)");
+    assert_equal!(vm.eval_string("(append '(1) nil '(2) nil)")?.to_string(), "(1 2)");
+    assert_equal!(vm.eval_string("(append)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(let ((x (list 2 3))) (eq (cdr (append '(1) x)) x))")?, Value::T);
+    Ok(())
+}
+
+#[test]
+fn test_cons_of_non_list_makes_dotted_pair() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(cons 1 2)")?.to_string(), "(1 . 2)");
+    assert_equal!(vm.eval_string("(cons 1 (cons 2 3))")?.to_string(), "(1 2 . 3)");
+    assert_equal!(vm.eval_string("(cons 1 '(2 3))")?.to_string(), "(1 2 3)");
+    assert_equal!(vm.eval_string("(cons 1 nil)")?.to_string(), "(1)");
+    assert_equal!(vm.eval_string("(car '(a . b))")?, Value::symbol("a"));
+    assert_equal!(vm.eval_string("(cdr '(a . b))")?, Value::symbol("b"));
+    assert_equal!(vm.eval_string("(cdr '(a b . c))")?.to_string(), "(b . c)");
+    assert_equal!(vm.eval_string("(equal '(1 . 2) (cons 1 2))")?, Value::T);
+    Ok(())
+}
+
+#[test]
+fn test_cons_shares_its_tail() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(let ((x (list 1 2))) (eq (cdr (cons 0 x)) x))")?, Value::T);
+    Ok(())
+}
+
+#[test]
+fn test_list_keeps_each_argument_as_an_element() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(list (list 1 2) 3)")?.to_string(), "((1 2) 3)");
+    assert_equal!(vm.eval_string("(list nil)")?.to_string(), "(nil)");
+    assert_equal!(vm.eval_string("(length (list nil nil))")?, Value::unsigned_integer(2u64));
+    assert_equal!(vm.eval_string("(list (cons 2 3))")?.to_string(), "((2 . 3))");
+    assert_equal!(vm.eval_string("(equal (apply 'list '((1 2) (3))) '((1 2) (3)))")?, Value::T);
+    assert_equal!(vm.eval_string("(alist-get 'a (list (cons 'a 1)))")?, Value::unsigned_integer(1u64));
+    assert_equal!(vm.eval_string("(mapcar 'cdr (list (cons 1 2)))")?.to_string(), "(2)");
+    Ok(())
+}
+
+#[test]
+fn test_setcar_and_setcdr_change_the_shared_cell() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq items (list 1 2 3) alias items)")?;
+    assert_equal!(vm.eval_string("(eq items alias)")?, Value::T);
+    assert_equal!(vm.eval_string("(setcar items 'x)")?, Value::quoted_symbol("x"));
+    assert_equal!(vm.eval_string("(car alias)")?, Value::quoted_symbol("x"));
+    assert_equal!(vm.eval_string("(setcdr items (list 4 5))")?.to_string(), "(4 5)");
+    assert_equal!(vm.eval_string("alias")?.to_string(), "('x 4 5)");
+    assert_equal!(vm.eval_string("(eq items alias)")?, Value::T);
+
+    vm.eval_string("(setq tail (cdr (list 1 2 3)) items (cons 0 tail))")?;
+    vm.eval_string("(setcar tail 'm)")?;
+    assert_equal!(vm.eval_string("items")?.to_string(), "(0 'm 3)");
+    Ok(())
+}
+
+#[test]
+fn test_setcar_and_setcdr_of_dotted_pairs() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq pair (cons 1 2) alias pair)")?;
+    vm.eval_string("(setcar pair 0)")?;
+    assert_equal!(vm.eval_string("alias")?.to_string(), "(0 . 2)");
+    vm.eval_string("(setcdr pair 3)")?;
+    assert_equal!(vm.eval_string("alias")?.to_string(), "(0 . 3)");
+    vm.eval_string("(setcdr pair (list 4 5))")?;
+    assert_equal!(vm.eval_string("alias")?.to_string(), "(0 4 5)");
+    vm.eval_string("(setcdr (cdr alias) 6)")?;
+    assert_equal!(vm.eval_string("pair")?.to_string(), "(0 4 . 6)");
+    assert_equal!(vm.eval_string("(setcar nil 1)").is_err(), true);
+    assert_equal!(vm.eval_string("(setcdr 'x 1)").is_err(), true);
+    Ok(())
+}
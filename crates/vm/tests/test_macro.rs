@@ -0,0 +1,86 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_defmacro_expands_before_evaluation() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defmacro inc (var) `(setq ,var (+ ,var 1)))
+(setq x 41)
+(inc x)
+x
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(42u32));
+    Ok(())
+}
+
+#[test]
+fn test_macro_arguments_are_not_evaluated() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defmacro first-symbol (a b) `(quote ,a))
+(first-symbol undefined (this is never evaluated))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "'(undefined)");
+    Ok(())
+}
+
+#[test]
+fn test_macro_with_rest_parameter() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defmacro my-progn (&rest body) `(car (cdr (list 0 ,@body))))
+(my-progn (+ 1 2) 4)
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(3u32));
+    Ok(())
+}
+
+#[test]
+fn test_macroexpand_1_expands_once() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        r#"
+(defmacro inc (var) `(setq ,var (+ ,var 1)))
+(defmacro inc-twice (var) `(inc (inc ,var)))
+"#,
+    )?;
+    assert_equal!(
+        vm.eval_string("(macroexpand-1 '(inc-twice x))")?.to_string(),
+        "'(inc (inc x))"
+    );
+    assert_equal!(
+        vm.eval_string("(macroexpand-1 '(+ 1 2))")?.to_string(),
+        "'(+ 1 2)"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_macroexpand_expands_until_not_a_macro_call() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        r#"
+(defmacro inc (var) `(setq ,var (+ ,var 1)))
+(defmacro inc-twice (var) `(inc (inc ,var)))
+"#,
+    )?;
+    assert_equal!(
+        vm.eval_string("(macroexpand '(inc-twice x))")?.to_string(),
+        "'(setq (inc x) (+ (inc x) 1))"
+    );
+    assert_equal!(
+        vm.eval_string("(macroexpand-all '(inc-twice x))")?.to_string(),
+        "'(setq (setq x (+ x 1)) (+ (setq x (+ x 1)) 1))"
+    );
+    Ok(())
+}
@@ -1,7 +1,10 @@
 #![allow(unused)]
+use std::str::FromStr;
+
 use k9::assert_equal;
-use sexprs_data_structures::{append, car, cdr, cons, list, Value};
-use sexprs_vm::{Result, VirtualMachine};
+use sexprs_data_structures::{append, car, cdr, cons, list, BigInt, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{ErrorType, Result, VirtualMachine};
 
 #[test]
 fn test_multiply_numbers() -> Result<()> {
@@ -92,3 +95,196 @@ fn test_eval_add_symbols() -> Result<()> {
     assert_equal!(val, Value::unsigned_integer(2u64));
     Ok(())
 }
+
+#[test]
+fn test_mixed_operands_promote_to_the_higher_type() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(+ 1 -2)")?, Value::integer(-1i64));
+    assert_equal!(vm.eval_string("(+ -2 1)")?, Value::integer(-1i64));
+    assert_equal!(vm.eval_string("(+ 1 2.5)")?, Value::float(3.5));
+    assert_equal!(vm.eval_string("(* 2.5 2)")?, Value::float(5.0));
+    assert_equal!(vm.eval_string("(- 10 -2.5 0.5)")?, Value::float(12.0));
+    Ok(())
+}
+
+#[test]
+fn test_unsigned_underflow_promotes_to_integer() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(- 1 5)")?, Value::integer(-4i64));
+    assert_equal!(vm.eval_string("(* 4294967295 2)")?, Value::integer(8589934590i64));
+    Ok(())
+}
+
+#[test]
+fn test_integer_division_is_exact() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(/ 12 4)")?, Value::unsigned_integer(3u32));
+    assert_equal!(vm.eval_string("(/ -12 4)")?, Value::integer(-3i64));
+    assert_equal!(vm.eval_string("(/ 7 2)")?, Value::ratio(7, 2));
+    assert_equal!(vm.eval_string("(/ 7 2.0)")?, Value::float(3.5));
+    Ok(())
+}
+
+#[test]
+fn test_integer_overflow_promotes_to_big_integers() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(* -9223372036854775807 2)")?.to_string(),
+        "-18446744073709551614"
+    );
+    assert_equal!(
+        vm.eval_string("(* 123456789012345678901234567890 10)")?,
+        Value::big_integer(BigInt::from_str("1234567890123456789012345678900").unwrap())
+    );
+    assert_equal!(
+        vm.eval_string("(- 123456789012345678901234567890 123456789012345678901234567889)")?,
+        Value::integer(1i64)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_ratio_arithmetic_is_exact() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(+ 1/3 1/6)")?, Value::ratio(1, 2));
+    assert_equal!(vm.eval_string("(* 3/4 4)")?, Value::integer(3i64));
+    assert_equal!(vm.eval_string("(- 1/10 1/5)")?.to_string(), "-1/10");
+    assert_equal!(vm.eval_string("(/ 1/2 1/4)")?, Value::integer(2i64));
+    assert_equal!(vm.eval_string("(+ 1/2 0.25)")?, Value::float(0.75));
+    Ok(())
+}
+
+#[test]
+fn test_division_by_zero_is_an_arithmetic_error() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let error = vm.eval_string("(/ 1 0)").expect_err("division by zero");
+    assert_equal!(error.ty(), ErrorType::ArithmeticError);
+    let error = vm.eval_string("(/ 1/2 0)").expect_err("division by zero");
+    assert_equal!(error.ty(), ErrorType::ArithmeticError);
+    assert_equal!(
+        vm.eval_string("(condition-case err (/ 1 0) (arith-error (car err)))")?,
+        Value::symbol("arith-error")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_abs() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(abs -3)")?, Value::integer(3i64));
+    assert_equal!(vm.eval_string("(abs -3/4)")?, Value::ratio(3, 4));
+    assert_equal!(vm.eval_string("(abs -2.5)")?, Value::float(2.5));
+    assert_equal!(
+        vm.eval_string("(abs -9223372036854775808)")?.to_string(),
+        "9223372036854775808"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_rounding() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(floor 7 2)")?, Value::unsigned_integer(3u32));
+    assert_equal!(vm.eval_string("(floor -7 2)")?, Value::integer(-4i64));
+    assert_equal!(vm.eval_string("(ceiling 7 2)")?, Value::unsigned_integer(4u32));
+    assert_equal!(vm.eval_string("(truncate -7 2)")?, Value::integer(-3i64));
+    assert_equal!(vm.eval_string("(round 5/2)")?, Value::integer(2i64));
+    assert_equal!(vm.eval_string("(round 7/2)")?, Value::integer(4i64));
+    assert_equal!(vm.eval_string("(round -2.5)")?, Value::integer(-2i64));
+    assert_equal!(vm.eval_string("(floor 2.7)")?, Value::integer(2i64));
+    Ok(())
+}
+
+#[test]
+fn test_mod_and_rem() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(mod 7 3)")?, Value::unsigned_integer(1u32));
+    assert_equal!(vm.eval_string("(mod -7 3)")?, Value::integer(2i64));
+    assert_equal!(vm.eval_string("(rem -7 3)")?, Value::integer(-1i64));
+    assert_equal!(vm.eval_string("(mod 7 -3)")?, Value::integer(-2i64));
+    assert_equal!(vm.eval_string("(mod 5.5 2)")?, Value::float(1.5));
+    assert_equal!(vm.eval_string("(mod 7/2 1)")?, Value::ratio(1, 2));
+    let error = vm.eval_string("(mod 1 0)").expect_err("division by zero");
+    assert_equal!(error.ty(), ErrorType::ArithmeticError);
+    Ok(())
+}
+
+#[test]
+fn test_expt_is_exact_for_exact_bases() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(expt 2 10)")?, Value::unsigned_integer(1024u32));
+    assert_equal!(
+        vm.eval_string("(expt 2 100)")?.to_string(),
+        "1267650600228229401496703205376"
+    );
+    assert_equal!(vm.eval_string("(expt 2 -2)")?, Value::ratio(1, 4));
+    assert_equal!(vm.eval_string("(expt 2/3 2)")?, Value::ratio(4, 9));
+    assert_equal!(vm.eval_string("(expt 4 0.5)")?, Value::float(2.0));
+    let error = vm.eval_string("(expt 0 -1)").expect_err("division by zero");
+    assert_equal!(error.ty(), ErrorType::ArithmeticError);
+    Ok(())
+}
+
+#[test]
+fn test_float_functions() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(sqrt 16)")?, Value::float(4.0));
+    assert_equal!(vm.eval_string("(exp 0)")?, Value::float(1.0));
+    assert_equal!(vm.eval_string("(log 1)")?, Value::float(0.0));
+    assert_equal!(vm.eval_string("(log 8 2)")?, Value::float(3.0));
+    assert_equal!(vm.eval_string("(sin 0)")?, Value::float(0.0));
+    assert_equal!(vm.eval_string("(cos 0)")?, Value::float(1.0));
+    assert_equal!(vm.eval_string("(tan 0)")?, Value::float(0.0));
+    assert_equal!(vm.eval_string("(asin 1)")?, Value::float(std::f64::consts::FRAC_PI_2));
+    assert_equal!(vm.eval_string("(acos 1)")?, Value::float(0.0));
+    assert_equal!(vm.eval_string("(atan 1 -1)")?, Value::float(3.0 * std::f64::consts::FRAC_PI_4));
+    let error = vm.eval_string("(sqrt -1)").expect_err("out of domain");
+    assert_equal!(error.ty(), ErrorType::ArithmeticError);
+    assert_equal!(error.message().ends_with("argument out of domain: (sqrt -1)"), true);
+    Ok(())
+}
+
+#[test]
+fn test_number_predicates() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(zerop 0.0)")?, Value::T);
+    assert_equal!(vm.eval_string("(zerop 1/2)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(evenp -4)")?, Value::T);
+    assert_equal!(vm.eval_string("(oddp 123456789012345678901234567891)")?, Value::T);
+    let error = vm.eval_string("(evenp 1.5)").expect_err("not an integer");
+    assert_equal!(error.message().ends_with("evenp called with non-integer value: 1.5"), true);
+    Ok(())
+}
+
+#[test]
+fn test_bitwise_operations() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(logand 12 10)")?, Value::unsigned_integer(8u32));
+    assert_equal!(vm.eval_string("(logior 12 10)")?, Value::unsigned_integer(14u32));
+    assert_equal!(vm.eval_string("(logxor 12 10)")?, Value::unsigned_integer(6u32));
+    assert_equal!(vm.eval_string("(logand -1 255)")?, Value::integer(255i64));
+    assert_equal!(vm.eval_string("(logand)")?, Value::integer(-1i64));
+    assert_equal!(vm.eval_string("(ash 1 10)")?, Value::unsigned_integer(1024u32));
+    assert_equal!(vm.eval_string("(ash -8 -1)")?, Value::integer(-4i64));
+    assert_equal!(vm.eval_string("(ash -1 -10)")?, Value::integer(-1i64));
+    assert_equal!(vm.eval_string("(ash 1 64)")?.to_string(), "18446744073709551616");
+    Ok(())
+}
+
+#[test]
+fn test_arithmetic_with_fewer_than_two_arguments() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(+)")?, Value::unsigned_integer(0u32));
+    assert_equal!(vm.eval_string("(*)")?, Value::unsigned_integer(1u32));
+    assert_equal!(vm.eval_string("(-)")?, Value::unsigned_integer(0u32));
+    assert_equal!(vm.eval_string("(+ 5)")?, Value::unsigned_integer(5u32));
+    assert_equal!(vm.eval_string("(* 5)")?, Value::unsigned_integer(5u32));
+    assert_equal!(vm.eval_string("(- 5)")?, Value::integer(-5));
+    assert_equal!(vm.eval_string("(- -2.5)")?, Value::float(2.5));
+    assert_equal!(vm.eval_string("(/ 4)")?.to_string(), "1/4");
+    assert_equal!(vm.eval_string("(/ 0.5)")?, Value::float(2.0));
+    assert_equal!(vm.eval_string("(/)").is_err(), true);
+    assert_equal!(vm.eval_string("(reduce '+ '())")?, Value::unsigned_integer(0u32));
+    assert_equal!(vm.eval_string("(reduce '* '())")?, Value::unsigned_integer(1u32));
+    Ok(())
+}
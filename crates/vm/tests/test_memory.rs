@@ -0,0 +1,67 @@
+#![allow(unused)]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+use k9::assert_equal;
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+/// counts the bytes allocated and not yet freed
+struct Counting;
+
+static LIVE: AtomicIsize = AtomicIsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(layout.size() as isize, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size() as isize, Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// the bytes still allocated after evaluating `source`, which is
+/// evaluated once beforehand so that only its growth is measured
+fn growth(vm: &mut VirtualMachine, source: &str) -> Result<isize> {
+    vm.eval_string(source)?;
+    let before = LIVE.load(Ordering::SeqCst);
+    vm.eval_string(source)?;
+    Ok(LIVE.load(Ordering::SeqCst) - before)
+}
+
+#[test]
+fn test_loops_run_in_bounded_memory() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        r#"
+(defun count-down (n)
+  (if (= n 0)
+      'done
+      (count-down (- n 1))))
+(defun pair-of (n)
+  (list n (* n 2)))
+"#,
+    )?;
+    for source in [
+        r#"(dotimes (i 20000) (concat "a" "b"))"#,
+        "(dotimes (i 20000) (pair-of i))",
+        "(count-down 20000)",
+        "(let ((items nil)) (dotimes (i 20000) (setq items (cons i items))) (length items))",
+    ] {
+        let growth = growth(&mut vm, source)?;
+        assert_equal!(growth < 64 * 1024, true, "{} kept {} bytes", source, growth);
+    }
+
+    let mut growths = Vec::new();
+    for _ in 0..3 {
+        growths.push(growth(&mut vm, "(count-down 2000)")?);
+    }
+    assert_equal!(growths.iter().all(|growth| *growth < 4 * 1024), true, "{:?}", growths);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_plist_get_and_member() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq options '(:width 80 :height 24 :title nil))")?;
+    assert_equal!(vm.eval_string("(plist-get options :height)")?, Value::unsigned_integer(24u32));
+    assert_equal!(vm.eval_string("(plist-get options :depth)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(plist-get options 80)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(plist-member options :title)")?.to_string(), "(:title nil)");
+    assert_equal!(vm.eval_string("(plist-member options :depth)")?, Value::Nil);
+    assert_equal!(vm.eval_string(r#"(plist-get '("a" 1 "b" 2) "b" 'equal)"#)?, Value::unsigned_integer(2u32));
+    assert_equal!(vm.eval_string(r#"(plist-get '("a" 1 "b" 2) "b")"#)?, Value::Nil);
+    Ok(())
+}
+
+#[test]
+fn test_plist_put_returns_the_updated_plist() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(plist-put nil :a 1)")?.to_string(), "(:a 1)");
+    vm.eval_string("(setq options (plist-put '(:a 1 :b 2) :b 3))")?;
+    assert_equal!(vm.eval_string("options")?.to_string(), "(:a 1 :b 3)");
+    vm.eval_string("(setq options (plist-put options :c 4))")?;
+    assert_equal!(vm.eval_string("options")?.to_string(), "(:a 1 :b 3 :c 4)");
+    assert_equal!(vm.eval_string("(plist-put '(:a) :a 1)")?.to_string(), "(:a 1)");
+    Ok(())
+}
+
+#[test]
+fn test_symbol_property_lists() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(symbol-plist 'widget)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(put 'widget 'color \"red\")")?, Value::from("red"));
+    vm.eval_string("(put 'widget :size 3)")?;
+    vm.eval_string("(put 'widget 'color \"blue\")")?;
+    assert_equal!(vm.eval_string("(get 'widget 'color)")?, Value::from("blue"));
+    assert_equal!(vm.eval_string("(get 'widget :size)")?, Value::unsigned_integer(3u32));
+    assert_equal!(vm.eval_string("(get 'widget 'weight)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(get 'gadget 'color)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(symbol-plist 'widget)")?.to_string(), r#"('color "blue" :size 3)"#);
+
+    let error = vm.eval_string("(put \"widget\" 'color nil)").expect_err("not a symbol");
+    assert_equal!(error.message().contains("put called with non-symbol value: \"widget\""), true);
+    Ok(())
+}
+
+#[test]
+fn test_symbol_properties_are_independent_of_bindings() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq widget 1)")?;
+    vm.eval_string("(defun describe () (get 'widget 'doc))")?;
+    vm.eval_string("(put 'widget 'doc \"a widget\")")?;
+    assert_equal!(vm.eval_string("(let ((widget 2)) (describe))")?, Value::from("a widget"));
+    assert_equal!(vm.eval_string("widget")?, Value::unsigned_integer(1u32));
+    Ok(())
+}
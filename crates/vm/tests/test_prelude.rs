@@ -0,0 +1,98 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{ErrorType, Result, VirtualMachine, PRELUDE};
+
+#[test]
+fn test_prelude_list_accessors() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq tree '((a b) c d))")?;
+    assert_equal!(vm.eval_string("(caar tree)")?.to_string(), "a");
+    assert_equal!(vm.eval_string("(cadr tree)")?.to_string(), "c");
+    assert_equal!(vm.eval_string("(cdar tree)")?.to_string(), "(b)");
+    assert_equal!(vm.eval_string("(cddr tree)")?.to_string(), "(d)");
+    assert_equal!(vm.eval_string("(caddr tree)")?.to_string(), "d");
+    Ok(())
+}
+
+#[test]
+fn test_prelude_when_and_unless_are_macros() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(macroexpand '(when ready (start) (wait)))")?.to_string(),
+        "'(if ready (progn (start) (wait)))"
+    );
+    assert_equal!(
+        vm.eval_string("(macroexpand '(unless ready (wait)))")?.to_string(),
+        "'(if ready nil (progn (wait)))"
+    );
+    vm.eval_string("(defun count-down (n) (unless (= n 0) (count-down (- n 1))))")?;
+    assert_equal!(vm.eval_string("(count-down 10000)")?, Value::Nil);
+    Ok(())
+}
+
+#[test]
+fn test_prelude_flatten() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(flatten '(1 (2 (3 nil 4)) ((5))))")?.to_string(), "(1 2 3 4 5)");
+    assert_equal!(vm.eval_string("(flatten '(a . b))")?.to_string(), "(a b)");
+    assert_equal!(vm.eval_string("(flatten nil)")?, Value::Nil);
+    Ok(())
+}
+
+#[test]
+fn test_bare_virtual_machine_has_builtins_only() -> Result<()> {
+    let mut vm = VirtualMachine::bare();
+    assert_equal!(vm.eval_string("(car '(1 2))")?, Value::unsigned_integer(1u32));
+    let error = vm.eval_string("(cadr '(1 2))").expect_err("cadr is defined by the prelude");
+    assert_equal!(error.ty(), ErrorType::UnboundFunction);
+    let error = vm.eval_string("(when t 1)").expect_err("when is defined by the prelude");
+    assert_equal!(error.ty(), ErrorType::UnboundFunction);
+    Ok(())
+}
+
+#[test]
+fn test_prelude_extends_the_standard_one() -> Result<()> {
+    let mut vm = VirtualMachine::with_prelude(
+        "(defun second-celsius (list) (celsius (cadr list)))
+(defun celsius (f) (/ (* (- f 32) 5) 9))",
+        None,
+    )?;
+    assert_equal!(vm.eval_string("(second-celsius '(0 212))")?, Value::unsigned_integer(100u32));
+    assert_equal!(vm.eval_string("(when t (caddr '(1 2 3)))")?, Value::unsigned_integer(3u32));
+    assert_equal!(vm.backtrace().is_empty(), true);
+
+    let mut vm = VirtualMachine::with_prelude("(defun cadr (list) 'redefined)", None)?;
+    assert_equal!(vm.eval_string("(cadr '(1 2))")?.to_string(), "'redefined");
+    Ok(())
+}
+
+#[test]
+fn test_custom_prelude_replaces_the_standard_one() -> Result<()> {
+    let mut vm = VirtualMachine::with_custom_prelude("(defun celsius (f) (/ (* (- f 32) 5) 9))", None)?;
+    assert_equal!(vm.eval_string("(celsius 212)")?, Value::unsigned_integer(100u32));
+    let error = vm.eval_string("(cadr '(1 2))").expect_err("not in the custom prelude");
+    assert_equal!(error.ty(), ErrorType::UnboundFunction);
+
+    let mut vm = VirtualMachine::with_custom_prelude(PRELUDE, Some("prelude.lisp"))?;
+    vm.eval_program("(defun second-celsius (list) (celsius (cadr list)))")?;
+    vm.eval_program("(defun celsius (f) (/ (* (- f 32) 5) 9))")?;
+    assert_equal!(vm.eval_string("(second-celsius '(0 32))")?, Value::unsigned_integer(0u32));
+    Ok(())
+}
+
+#[test]
+fn test_failing_custom_prelude_is_an_error() -> Result<()> {
+    let error =
+        VirtualMachine::with_custom_prelude("(defun ok () t)\n(undefined-function)", None)
+            .expect_err("the prelude calls an undefined function");
+    assert_equal!(error.ty(), ErrorType::UnboundFunction);
+    assert_equal!(error.message().contains("form 2 of 2 failed: (undefined-function)"), true);
+    let error = VirtualMachine::with_prelude("(undefined-function)", Some("domain.lisp"))
+        .expect_err("the extra definitions call an undefined function");
+    assert_equal!(error.ty(), ErrorType::UnboundFunction);
+    assert_equal!(error.to_string().contains("domain.lisp"), true);
+    assert_equal!(error.to_string().contains("prelude.lisp"), false);
+    Ok(())
+}
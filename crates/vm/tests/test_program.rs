@@ -0,0 +1,88 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_eval_program_evaluates_every_form_and_returns_the_last_value() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_program(
+        r#"
+(defun sum (a b) (+ a b))
+(setq x 40)
+(sum x 2)
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(42u32));
+    Ok(())
+}
+
+#[test]
+fn test_eval_program_of_empty_source_returns_nil() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_program("")?, Value::nil());
+    Ok(())
+}
+
+#[test]
+fn test_eval_program_error_mentions_failing_form() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let error = vm
+        .eval_program(
+            r#"
+(setq x 1)
+(+ 1 "one")
+(setq y 2)
+"#,
+        )
+        .expect_err("second form should fail");
+    assert_equal!(error.message().starts_with("form 2 of 3 failed: (+ 1 \"one\"): "), true);
+    Ok(())
+}
+
+#[test]
+fn test_eval_program_does_not_borrow_its_source() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    {
+        let source = String::from("(defun twice (x) (* 2 x))");
+        vm.eval_program(&source)?;
+    }
+    assert_equal!(vm.eval_program("(twice 21)")?, Value::unsigned_integer(42u32));
+    Ok(())
+}
+
+#[test]
+fn test_eval_file() -> Result<()> {
+    let path = std::env::temp_dir().join("sexprs-vm-test-eval-file.lisp");
+    std::fs::write(
+        &path,
+        r#"
+(defun double (n) (* n 2))
+(double 21)
+"#,
+    )?;
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_file(path.display())?;
+    std::fs::remove_file(&path)?;
+    assert_equal!(value, Value::unsigned_integer(42u32));
+    Ok(())
+}
+
+#[test]
+fn test_eval_program_with_comments() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_program(
+        r#"
+;;; sums its arguments
+(defun sum (a b)
+  #| the body is a
+     single call |#
+  (+ a b))
+#;(sum 1 1)
+(sum 40 2) ; => 42
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(42u32));
+    Ok(())
+}
@@ -0,0 +1,64 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_backquote_without_unquote_behaves_like_quote() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("`(a b c)")?.to_string(), "'(a b c)");
+    assert_equal!(vm.eval_string("`a")?.to_string(), "'a");
+    Ok(())
+}
+
+#[test]
+fn test_unquote_evaluates_the_form() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq b 1)")?;
+    assert_equal!(vm.eval_string("`(a ,b ,(+ b 2))")?.to_string(), "'(a 1 3)");
+    assert_equal!(vm.eval_string("`((nested ,b))")?.to_string(), "'((nested 1))");
+    Ok(())
+}
+
+#[test]
+fn test_unquote_splicing_inserts_the_elements() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq rest '(c d))")?;
+    assert_equal!(vm.eval_string("`(a ,@rest e)")?.to_string(), "'(a c d e)");
+    assert_equal!(vm.eval_string("`(a ,@nil)")?.to_string(), "'(a)");
+    Ok(())
+}
+
+#[test]
+fn test_nested_backquote_only_expands_innermost_level() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq x 1)")?;
+    assert_equal!(
+        vm.eval_string("`(a `(b ,(c ,x)))")?.to_string(),
+        "'(a (backquote (b (unquote (c 1)))))"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unquote_outside_backquote_is_an_error() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq x 1)")?;
+    let error = vm.eval_string(",x").expect_err("unquote outside of backquote");
+    assert_equal!(error.message().contains("unquote outside of backquote"), true);
+    let error = vm.eval_string("`(a ,@x)").expect_err("splicing a non-list");
+    assert_equal!(error.message().contains("unquote-splicing of non-list value"), true);
+    Ok(())
+}
+
+#[test]
+fn test_dotted_templates_keep_their_cdr() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq b 5)")?;
+    assert_equal!(vm.eval_string("`(a . ,b)")?.to_string(), "'(a . 5)");
+    assert_equal!(vm.eval_string("`(,@(list 1 2) . 3)")?.to_string(), "'(1 2 . 3)");
+    assert_equal!(vm.eval_string("`(a ,@(list 1 2) . ,b)")?.to_string(), "'(a 1 2 . 5)");
+    assert_equal!(vm.eval_string("`(a b . c)")?.to_string(), "'(a b . c)");
+    Ok(())
+}
@@ -0,0 +1,152 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_mapcar_with_builtin_and_user_functions() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(defun square (x) (* x x))")?;
+    assert_equal!(vm.eval_string("(mapcar 'square '(1 2 3))")?.to_string(), "(1 4 9)");
+    assert_equal!(vm.eval_string("(mapcar 'abs [-1 2 -3])")?.to_string(), "(1 2 3)");
+    assert_equal!(vm.eval_string("(mapcar (lambda (x) (+ x 1)) '(1 2))")?.to_string(), "(2 3)");
+    assert_equal!(vm.eval_string("(mapcar 'char-to-string \"ab\")")?.to_string(), r#"("a" "b")"#);
+    assert_equal!(vm.eval_string("(mapcar 'square nil)")?.to_string(), "()");
+    let error = vm.eval_string("(mapcar 'square 3)").expect_err("not a sequence");
+    assert_equal!(error.message().contains("mapcar called with non-sequence value: 3"), true);
+    Ok(())
+}
+
+#[test]
+fn test_mapc_and_mapcan() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq total 0)")?;
+    assert_equal!(
+        vm.eval_string("(mapc (lambda (x) (setq total (+ total x))) '(1 2 3))")?.to_string(),
+        "'(1 2 3)"
+    );
+    assert_equal!(vm.eval_string("total")?, Value::unsigned_integer(6u32));
+    assert_equal!(
+        vm.eval_string("(mapcan (lambda (x) (if (oddp x) (list x x) nil)) '(1 2 3))")?.to_string(),
+        "(1 1 3 3)"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_seq_filter_and_remove_if() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(seq-filter 'evenp '(1 2 3 4))")?.to_string(), "(2 4)");
+    assert_equal!(vm.eval_string("(remove-if 'evenp '(1 2 3 4))")?.to_string(), "(1 3)");
+    assert_equal!(vm.eval_string("(seq-filter (lambda (x) (> x 5)) [3 6 9])")?.to_string(), "(6 9)");
+    Ok(())
+}
+
+#[test]
+fn test_reduce_and_fold() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(reduce '+ '(1 2 3 4))")?, Value::unsigned_integer(10u32));
+    assert_equal!(vm.eval_string("(reduce (lambda () 'empty) nil)")?.to_string(), "'empty");
+    assert_equal!(vm.eval_string("(reduce '- '(10))")?, Value::unsigned_integer(10u32));
+    assert_equal!(vm.eval_string("(reduce '- '(1 2) :initial-value 10)")?, Value::unsigned_integer(7u32));
+    assert_equal!(
+        vm.eval_string("(fold (lambda (acc x) (cons x acc)) nil '(1 2 3))")?.to_string(),
+        "(3 2 1)"
+    );
+    let error = vm.eval_string("(reduce '+ '(1) :from-end t)").expect_err("keyword");
+    assert_equal!(error.message().contains("reduce called with invalid keyword arguments: :from-end"), true);
+    Ok(())
+}
+
+#[test]
+fn test_sort_is_stable() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(sort '(3 1 2 5 4) '<)")?.to_string(), "(1 2 3 4 5)");
+    assert_equal!(vm.eval_string("(sort '(3 1 2) '>)")?.to_string(), "(3 2 1)");
+    assert_equal!(
+        vm.eval_string(
+            "(sort '((b . 1) (a . 2) (c . 1) (d . 2) (e . 1)) (lambda (x y) (< (cdr x) (cdr y))))"
+        )?
+        .to_string(),
+        "((b . 1) (c . 1) (e . 1) (a . 2) (d . 2))"
+    );
+    vm.eval_string("(setq v [3 1 2])")?;
+    vm.eval_string("(sort v '<)")?;
+    assert_equal!(vm.eval_string("v")?.to_string(), "[1 2 3]");
+    assert_equal!(vm.eval_string("(sort nil '<)")?.to_string(), "()");
+    vm.eval_string("(sort '(1 a) '<)").expect_err("comparing a symbol");
+    Ok(())
+}
+
+#[test]
+fn test_reverse_and_length() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(reverse '(1 2 3))")?.to_string(), "(3 2 1)");
+    assert_equal!(vm.eval_string("(reverse [1 2 3])")?.to_string(), "[3 2 1]");
+    assert_equal!(vm.eval_string("(reverse \"abc\")")?, Value::from("cba"));
+    assert_equal!(vm.eval_string("(reverse #u8(1 2))")?, Value::bytes([2u8, 1]));
+    assert_equal!(vm.eval_string("(length '(1 2 3))")?, Value::unsigned_integer(3u32));
+    assert_equal!(vm.eval_string("(length nil)")?, Value::unsigned_integer(0u32));
+    assert_equal!(vm.eval_string("(length [1 2])")?, Value::unsigned_integer(2u32));
+    assert_equal!(vm.eval_string("(length \"héllo\")")?, Value::unsigned_integer(5u32));
+    Ok(())
+}
+
+#[test]
+fn test_nth_nthcdr_last_and_butlast() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq items '(a b c d))")?;
+    assert_equal!(vm.eval_string("(nth 0 items)")?.to_string(), "a");
+    assert_equal!(vm.eval_string("(nth 2 items)")?.to_string(), "c");
+    assert_equal!(vm.eval_string("(nth 9 items)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(nthcdr 2 items)")?.to_string(), "(c d)");
+    assert_equal!(vm.eval_string("(nthcdr 9 items)")?, Value::Nil);
+    assert_equal!(vm.eval_string("(eq (nthcdr 2 items) (cdr (cdr items)))")?, Value::T);
+    assert_equal!(vm.eval_string("(last items)")?.to_string(), "(d)");
+    assert_equal!(vm.eval_string("(last items 2)")?.to_string(), "(c d)");
+    assert_equal!(vm.eval_string("(last '(a b . c))")?.to_string(), "(b . c)");
+    assert_equal!(vm.eval_string("(butlast items)")?.to_string(), "(a b c)");
+    assert_equal!(vm.eval_string("(butlast items 3)")?.to_string(), "(a)");
+    assert_equal!(vm.eval_string("(butlast items 9)")?.to_string(), "()");
+    Ok(())
+}
+
+#[test]
+fn test_member_delete_and_remove() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(member \"b\" '(\"a\" \"b\" \"c\"))")?.to_string(), r#"("b" "c")"#);
+    assert_equal!(vm.eval_string("(member 'z '(a b))")?, Value::Nil);
+    assert_equal!(vm.eval_string("(remove 2 '(1 2 3 2))")?.to_string(), "(1 3)");
+    assert_equal!(vm.eval_string("(remove '(a) '((a) (b)))")?.to_string(), "((b))");
+    assert_equal!(vm.eval_string("(delete 1 [1 2 1])")?.to_string(), "[2]");
+    vm.eval_string("(setq items '(1 2 3))")?;
+    vm.eval_string("(delete 2 items)")?;
+    assert_equal!(vm.eval_string("(length items)")?, Value::unsigned_integer(3u32));
+    Ok(())
+}
+
+#[test]
+fn test_number_sequence() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(number-sequence 1 5)")?.to_string(), "(1 2 3 4 5)");
+    assert_equal!(vm.eval_string("(number-sequence 0 10 3)")?.to_string(), "(0 3 6 9)");
+    assert_equal!(vm.eval_string("(number-sequence 5 1 -2)")?.to_string(), "(5 3 1)");
+    assert_equal!(vm.eval_string("(number-sequence 4)")?.to_string(), "(4)");
+    assert_equal!(vm.eval_string("(number-sequence 5 1)")?.to_string(), "()");
+    assert_equal!(vm.eval_string("(number-sequence 0 1 0.5)")?.to_string(), "(0 0.5 1)");
+    let error = vm.eval_string("(number-sequence 1 5 0)").expect_err("zero step");
+    assert_equal!(error.message().contains("number-sequence called with a step of zero"), true);
+    Ok(())
+}
+
+#[test]
+fn test_seq_uniq() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(seq-uniq '(1 2 1 \"a\" \"a\" (b) (b)))")?.to_string(), r#"(1 2 "a" (b))"#);
+    assert_equal!(
+        vm.eval_string("(seq-uniq '(1 2 11 12 3) (lambda (a b) (= (mod a 10) (mod b 10))))")?.to_string(),
+        "(1 2 3)"
+    );
+    Ok(())
+}
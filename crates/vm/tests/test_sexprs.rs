@@ -38,7 +38,7 @@ fn test_list_quoted_sexprs() -> Result<()> {
         "(list 'a 'b 'c)" => "('a 'b 'c)"
     );
     assert_eval_display!(
-        "(list '(x y z) 3) " => "(x y z 3)"
+        "(list '(x y z) 3) " => "('(x y z) 3)"
     );
     assert_eval_display!(
         "(car '('a 'b 'c))" => "'a"
@@ -50,7 +50,7 @@ fn test_list_quoted_sexprs() -> Result<()> {
         "'(x y z)" => "'(x y z)"
     );
     assert_eval_display!(
-        "(list '(x y z) 3) " => "(x y z 3)"
+        "(list '(x y z) 3) " => "('(x y z) 3)"
     );
     Ok(())
 }
@@ -1,6 +1,7 @@
 #![allow(unused)]
 use k9::assert_equal;
 use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
 use sexprs_vm::{Result, VirtualMachine};
 
 #[test]
@@ -22,3 +23,107 @@ fn test_print() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn test_concat_and_substring() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(concat \"foo\" nil \"bar\")")?, Value::from("foobar"));
+    assert_equal!(vm.eval_string("(concat)")?, Value::from(""));
+    assert_equal!(vm.eval_string("(substring \"héllo\" 1 3)")?, Value::from("él"));
+    assert_equal!(vm.eval_string("(substring \"hello\" -3)")?, Value::from("llo"));
+    assert_equal!(vm.eval_string("(string-length \"héllo\")")?, Value::unsigned_integer(5u32));
+    let error = vm.eval_string("(substring \"hello\" 4 9)").expect_err("index past the end");
+    assert_equal!(error.message().contains("substring called with out of range indices: 4 9"), true);
+    Ok(())
+}
+
+#[test]
+fn test_case_and_trim() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(string-upcase \"Hello\")")?, Value::from("HELLO"));
+    assert_equal!(vm.eval_string("(string-downcase \"Hello\")")?, Value::from("hello"));
+    assert_equal!(vm.eval_string("(string-trim \"  hello \")")?, Value::from("hello"));
+    Ok(())
+}
+
+#[test]
+fn test_split_and_join() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(split-string \"  a b\tc \")")?,
+        list([Value::from("a"), Value::from("b"), Value::from("c")])
+    );
+    assert_equal!(
+        vm.eval_string("(split-string \"a,,b\" \",\")")?,
+        list([Value::from("a"), Value::from(""), Value::from("b")])
+    );
+    assert_equal!(vm.eval_string("(string-join (split-string \"a b c\") \"-\")")?, Value::from("a-b-c"));
+    assert_equal!(vm.eval_string("(string-join '(\"a\" \"b\"))")?, Value::from("ab"));
+    Ok(())
+}
+
+#[test]
+fn test_search_and_replace() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(string-prefix-p \"he\" \"hello\")")?, Value::T);
+    assert_equal!(vm.eval_string("(string-suffix-p \"he\" \"hello\")")?, Value::Nil);
+    assert_equal!(vm.eval_string("(string-search \"l\" \"héllo\")")?, Value::unsigned_integer(2u32));
+    assert_equal!(vm.eval_string("(string-search \"l\" \"héllo\" 3)")?, Value::unsigned_integer(3u32));
+    assert_equal!(vm.eval_string("(string-search \"z\" \"hello\")")?, Value::Nil);
+    assert_equal!(vm.eval_string("(string-replace \"l\" \"L\" \"hello\")")?, Value::from("heLLo"));
+    Ok(())
+}
+
+#[test]
+fn test_number_and_symbol_conversion() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(number-to-string 3/4)")?, Value::from("3/4"));
+    assert_equal!(vm.eval_string("(number-to-string -12)")?, Value::from("-12"));
+    assert_equal!(vm.eval_string("(string-to-number \" 42 \")")?, Value::unsigned_integer(42u32));
+    assert_equal!(vm.eval_string("(string-to-number \"2.5\")")?, Value::float(2.5));
+    assert_equal!(vm.eval_string("(string-to-number \"abc\")")?, Value::unsigned_integer(0u32));
+    assert_equal!(vm.eval_string("(symbol-name 'foo)")?, Value::from("foo"));
+    assert_equal!(vm.eval_string("(eq (intern \"foo\") 'foo)")?, Value::T);
+    Ok(())
+}
+
+#[test]
+fn test_format() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(
+        vm.eval_string("(format \"%s has %d items at %.2f%% (%s)\" \"cart\" 3 12.345 '(a \"b\"))")?,
+        Value::from("cart has 3 items at 12.35% ((a \"b\"))")
+    );
+    assert_equal!(vm.eval_string("(format \"%d %f\" 7/2 1)")?, Value::from("3 1.000000"));
+    let error = vm.eval_string("(format \"%s %s\" 1)").expect_err("missing argument");
+    assert_equal!(error.message().contains("format called with not enough arguments"), true);
+    let error = vm.eval_string("(format \"%d\" \"x\")").expect_err("non-numerical argument");
+    assert_equal!(error.message().contains("format %d called with non-numerical value: \"x\""), true);
+    Ok(())
+}
+
+#[test]
+fn test_escapes_print_back_as_read() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(r#"(concat "a\"b" "\n\t\\")"#)?;
+    assert_equal!(value, Value::from("a\"b\n\t\\"));
+    assert_equal!(value.to_string(), r#""a\"b\n\t\\""#);
+    assert_equal!(vm.eval_string(r#"(list ?a ?\s #\newline)"#)?.to_string(), r#"(?a ?\s ?\n)"#);
+    Ok(())
+}
+
+#[test]
+fn test_character_conversion() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(char-to-string ?é)")?, Value::from("é"));
+    assert_equal!(vm.eval_string("(string-to-list \"ab\")")?, list([Value::char('a'), Value::char('b')]));
+    assert_equal!(vm.eval_string("(char-code ?A)")?, Value::unsigned_integer(65u32));
+    assert_equal!(vm.eval_string("(code-char 128512)")?, Value::char('\u{1F600}'));
+    assert_equal!(vm.eval_string("(eq ?a (code-char 97))")?, Value::T);
+    assert_equal!(vm.eval_string("(format \"%s%s\" ?a \"b\")")?, Value::from("ab"));
+    let error = vm.eval_string("(code-char -1)").expect_err("negative code point");
+    assert_equal!(error.message().contains("code-char called with invalid code point: -1"), true);
+    let error = vm.eval_string("(char-code \"a\")").expect_err("string is not a character");
+    assert_equal!(error.message().contains("char-code called with non-character value: \"a\""), true);
+    Ok(())
+}
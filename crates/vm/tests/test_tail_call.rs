@@ -0,0 +1,91 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_self_tail_recursion_runs_in_bounded_stack() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun count-down (n acc)
+  (case n
+    (0 acc)
+    (otherwise (count-down (- n 1) (+ acc 1)))))
+(count-down 10000 0)
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(10000u32));
+    Ok(())
+}
+
+#[test]
+fn test_tail_calls_through_if_cond_let_and_progn() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun loop-if (n) (if (null (case n (0 t))) (loop-if (- n 1)) 'done))
+(defun loop-cond (n) (cond ((case n (0 t)) 'done) (t (let ((m (- n 1))) (progn (loop-cond m))))))
+(list (loop-if 5000) (loop-cond 5000))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "('done 'done)");
+    Ok(())
+}
+
+#[test]
+fn test_tail_calls_through_and_and_or() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun loop-and (n) (and t (if (= n 0) 'ok (loop-and (- n 1)))))
+(defun loop-or (n) (or (= n 0) (loop-or (- n 1))))
+(list (loop-and 3000) (loop-or 3000) (and) (or))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "('ok t t nil)");
+    Ok(())
+}
+
+#[test]
+fn test_mutual_tail_recursion_with_labels() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(labels ((is-even (n) (case n (0 t) (otherwise (is-odd (- n 1)))))
+         (is-odd (n) (case n (0 nil) (otherwise (is-even (- n 1))))))
+  (list (is-even 10000) (is-odd 7)))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "(t t)");
+    Ok(())
+}
+
+#[test]
+fn test_non_tail_calls_still_return_to_their_caller() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun sum (l) (if (null l) 0 (+ (car l) (sum (cdr l)))))
+(sum '(1 2 3 4 5))
+"#,
+    )?;
+    assert_equal!(value, Value::unsigned_integer(15u32));
+    Ok(())
+}
+
+#[test]
+fn test_funcall_and_apply_in_tail_position_run_in_bounded_stack() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let value = vm.eval_string(
+        r#"
+(defun count-with-funcall (n) (if (= n 0) 'done (funcall 'count-with-funcall (- n 1))))
+(defun count-with-apply (n) (if (= n 0) 'done (apply 'count-with-apply (list (- n 1)))))
+(setq count-with-closure (lambda (n) (if (= n 0) 'done (funcall count-with-closure (- n 1)))))
+(list (count-with-funcall 20000) (count-with-apply 2000) (funcall count-with-closure 2000))
+"#,
+    )?;
+    assert_equal!(value.to_string(), "('done 'done 'done)");
+    Ok(())
+}
@@ -0,0 +1,58 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{ErrorType, Result, VirtualMachine};
+
+#[test]
+fn test_unbound_variable_names_the_symbol_and_its_location() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let error = vm
+        .eval_string("(setq count 1)\n(+ cout 1)")
+        .expect_err("cout is unbound");
+    assert_equal!(error.ty(), ErrorType::UnboundVariable);
+    assert_equal!(
+        error.message(),
+        "form 2 of 2 failed: (+ cout 1): unbound variable cout at line 2, column 4"
+    );
+    assert_equal!(error.symbol(), Some("cout".to_string()));
+    assert_equal!(error.location().map(|location| location.start_pos()), Some((2, 4)));
+    Ok(())
+}
+
+#[test]
+fn test_unbound_variable_is_located_in_the_form_being_evaluated() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string(
+        "(defun describe ()
+  (list 'total \"total:\"
+        (+ 1 total)))",
+    )?;
+    let error = vm.eval_string("(describe)").expect_err("total is unbound");
+    assert_equal!(error.ty(), ErrorType::UnboundVariable);
+    assert_equal!(error.symbol(), Some("total".to_string()));
+    assert_equal!(error.location().map(|location| location.start_pos()), Some((3, 14)));
+    Ok(())
+}
+
+#[test]
+fn test_unbound_function_names_the_symbol() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    let error = vm.eval_string("(frobnicate 1 2)").expect_err("frobnicate is unbound");
+    assert_equal!(error.ty(), ErrorType::UnboundFunction);
+    assert_equal!(
+        error.message(),
+        "form 1 of 1 failed: (frobnicate 1 2): unbound function frobnicate at line 1, column 2"
+    );
+    assert_equal!(error.symbol(), Some("frobnicate".to_string()));
+    Ok(())
+}
+
+#[test]
+fn test_self_evaluating_symbols_is_opt_in() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.set_self_evaluating_symbols(true);
+    assert_equal!(vm.eval_string("undefined")?, Value::symbol("undefined"));
+    assert_equal!(vm.eval_string("(undefined 1)")?.to_string(), "(undefined 1)");
+    Ok(())
+}
@@ -0,0 +1,83 @@
+#![allow(unused)]
+use k9::assert_equal;
+use sexprs_data_structures::{list, Value};
+use sexprs_util::Traceback;
+use sexprs_vm::{Result, VirtualMachine};
+
+#[test]
+fn test_vector_literals_evaluate_to_themselves() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("[1 a \"b\"]")?.to_string(), r#"[1 a "b"]"#);
+    assert_equal!(vm.eval_string("#u8(1 2)")?, Value::bytes([1u8, 2]));
+    assert_equal!(vm.eval_string("(vector 1 (+ 1 1))")?.to_string(), "[1 2]");
+    Ok(())
+}
+
+#[test]
+fn test_make_vector_and_vector_length() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(make-vector 3 ?x)")?.to_string(), "[?x ?x ?x]");
+    assert_equal!(vm.eval_string("(vector-length (make-vector 3 nil))")?, Value::unsigned_integer(3u32));
+    assert_equal!(vm.eval_string("(vector-length #u8(1 2))")?, Value::unsigned_integer(2u32));
+    let error = vm.eval_string("(make-vector -1 nil)").expect_err("negative length");
+    assert_equal!(error.message().contains("negative length: -1"), true);
+    let error = vm.eval_string("(vector-length '(1 2))").expect_err("not an array");
+    assert_equal!(error.message().contains("vector-length called with non-array value"), true);
+    Ok(())
+}
+
+#[test]
+fn test_aref_and_aset() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq v [a b c])")?;
+    assert_equal!(vm.eval_string("(aref v 1)")?, Value::symbol("b"));
+    assert_equal!(vm.eval_string("(aset v 1 ?z)")?, Value::char('z'));
+    assert_equal!(vm.eval_string("v")?.to_string(), "[a ?z c]");
+    assert_equal!(vm.eval_string("(aref #u8(7 255) 1)")?, Value::unsigned_integer(255u32));
+    assert_equal!(vm.eval_string("(aref \"abc\" 2)")?, Value::char('c'));
+
+    vm.eval_string("(setq b (make-vector 0 nil))")?;
+    vm.eval_string("(setq b (list-to-bytes '(1 2)))")?;
+    assert_equal!(vm.eval_string("(aset b 0 200)")?, Value::unsigned_integer(200u32));
+    assert_equal!(vm.eval_string("b")?, Value::bytes([200u8, 2]));
+
+    let error = vm.eval_string("(aref v 3)").expect_err("out of range");
+    assert_equal!(error.message().contains("aref called with out of range index: 3"), true);
+    let error = vm.eval_string("(aset v -1 nil)").expect_err("out of range");
+    assert_equal!(error.message().contains("aset called with out of range index: -1"), true);
+    let error = vm.eval_string("(aset b 0 256)").expect_err("not a byte");
+    assert_equal!(error.message().contains("aset called with non-byte value: 256"), true);
+    let error = vm.eval_string("(aref '(a) 0)").expect_err("not an array");
+    assert_equal!(error.message().contains("aref called with non-array value"), true);
+    Ok(())
+}
+
+#[test]
+fn test_vectors_are_shared_by_reference() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    vm.eval_string("(setq v [1 2])")?;
+    vm.eval_string("(setq w v)")?;
+    vm.eval_string("(aset w 0 9)")?;
+    assert_equal!(vm.eval_string("v")?.to_string(), "[9 2]");
+    assert_equal!(vm.eval_string("(eq v w)")?, Value::T);
+    assert_equal!(vm.eval_string("(eq v [9 2])")?, Value::Nil);
+    assert_equal!(vm.eval_string("(equal v [9 2])")?, Value::T);
+    Ok(())
+}
+
+#[test]
+fn test_vconcat_and_conversions() -> Result<()> {
+    let mut vm = VirtualMachine::new();
+    assert_equal!(vm.eval_string("(vconcat [1] '(2 3) #u8(4) nil)")?.to_string(), "[1 2 3 4]");
+    assert_equal!(vm.eval_string("(vconcat)")?.to_string(), "[]");
+    assert_equal!(vm.eval_string("(vector-to-list [a b])")?.to_string(), "(a b)");
+    assert_equal!(vm.eval_string("(vector-to-list #u8(1 2))")?.to_string(), "(1 2)");
+    assert_equal!(vm.eval_string("(vector-to-list [])")?.to_string(), "()");
+    assert_equal!(vm.eval_string("(list-to-vector '(a b))")?.to_string(), "[a b]");
+    assert_equal!(vm.eval_string("(list-to-bytes '(0 255))")?, Value::bytes([0u8, 255]));
+    let error = vm.eval_string("(vconcat 1)").expect_err("not a sequence");
+    assert_equal!(error.message().contains("vconcat called with non-sequence value: 1"), true);
+    let error = vm.eval_string("(list-to-bytes '(1 a))").expect_err("not a byte");
+    assert_equal!(error.message().contains("list-to-bytes called with non-byte value: a"), true);
+    Ok(())
+}